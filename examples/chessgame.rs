@@ -15,7 +15,7 @@ fn input(prompt: &str, placeholder: &str, multiline: bool) -> Result<String, IoE
     resp = resp.chars().take(resp.len() - 1).collect();
     let resplen = resp.len();
     if resplen < phlen {
-        print!("\x1b[1A\x1b[{}C{}\n", if multiline { resplen } else { prompt.len() + resplen }, " ".repeat(phlen - resplen));
+        println!("\x1b[1A\x1b[{}C{}", if multiline { resplen } else { prompt.len() + resplen }, " ".repeat(phlen - resplen));
         let _ = io::stdout().flush();
     }
     Ok(resp)
@@ -61,7 +61,7 @@ fn ask_move(board: &mut Board, ascii: bool) {
     loop {
         let user_move_resp = input(
             "Please enter your move: ",
-            &format!(r#"____ (e.g. "{}")"#, board.move_to_san(legal.choose(&mut thread_rng()).unwrap().clone()).unwrap()),
+            &format!(r#"____ (e.g. "{}")"#, board.move_to_san(*legal.choose(&mut thread_rng()).unwrap()).unwrap()),
             false,
         )
         .expect("Failed to get user input")
@@ -96,8 +96,8 @@ fn main() {
         if user_turn {
             ask_move(&mut board, ascii);
         } else {
-            let move_ = board.gen_legal_moves().choose(&mut rng).unwrap().clone();
-            let san = board.move_to_san(move_.clone()).unwrap();
+            let move_ = *board.gen_legal_moves().choose(&mut rng).unwrap();
+            let san = board.move_to_san(move_).unwrap();
             board.make_move(move_).unwrap();
             println!("The computer played {san}.");
         }
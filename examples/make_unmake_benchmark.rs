@@ -0,0 +1,41 @@
+//! # A comparison of the two ways of playing moves on a `Position`
+//! Walks the legal move tree of the starting position to the given depth, first by copying the position with
+//! `Position::with_move_made`, then by playing and taking back moves with `Position::make_move_in_place` and `Position::unmake_move`.
+
+use rschess::{Board, Position};
+use std::{env, time::Instant};
+
+fn walk_copying(position: &Position, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    position
+        .gen_non_illegal_moves()
+        .into_iter()
+        .map(|m| walk_copying(&position.with_move_made(m).unwrap(), depth - 1))
+        .sum()
+}
+
+fn walk_in_place(position: &mut Position, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut nodes = 0;
+    for m in position.gen_non_illegal_moves() {
+        let undo = position.make_move_in_place(m).unwrap();
+        nodes += walk_in_place(position, depth - 1);
+        position.unmake_move(undo);
+    }
+    nodes
+}
+
+fn main() {
+    let depth: usize = if let Some(n) = env::args().nth(1) { n.parse().expect("Invalid depth") } else { 3 };
+    let position = Board::default().position().clone();
+    let start = Instant::now();
+    let nodes = walk_copying(&position, depth);
+    println!("with_move_made: {nodes} nodes in {:?}", start.elapsed());
+    let start = Instant::now();
+    let nodes = walk_in_place(&mut position.clone(), depth);
+    println!("make_move_in_place/unmake_move: {nodes} nodes in {:?}", start.elapsed());
+}
@@ -1,8 +1,11 @@
 use super::{
-    helpers, Color, DrawType, Fen, GameOverError, GameResult, IllegalMoveError, InvalidSanMoveError, InvalidSquareNameError, InvalidUciMoveError, Move, NoMovesPlayedError, Piece, PieceType, Position,
-    WinType,
+    helpers, zobrist, CastlingRights, Clock, Color, DrawType, Fen, GameOverError, GameResult, IllegalMoveError, InvalidEpdError, InvalidLanMoveError, InvalidPositionError, InvalidSanMoveError,
+    InvalidSquareNameError, InvalidUciMoveError, Move, NoMovesPlayedError, OpeningBook, PgnParseError, PgnTags, Piece, PieceType, Position, PrintOptions, SpecialMoveType, Termination, TimeControl,
+    UciPositionError, WinType,
 };
-use std::fmt;
+#[cfg(feature = "img")]
+use super::{img, InvalidPositionImagePropertiesError};
+use std::{fmt, time::Duration};
 
 /// The structure for a chessboard/game
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
@@ -23,38 +26,103 @@ pub struct Board {
     halfmove_clock_history: Vec<usize>,
     /// The FEN string representing the initial game state
     initial_fen: Fen,
-    /// The side that has resigned (or lost by timeout)
+    /// The side that has resigned
     resigned_side: Option<Color>,
-    /// Whether a draw has been made by agreement (or claimed)
-    draw_agreed: bool,
+    /// The reason the game has been drawn, if it has been drawn
+    draw_reason: Option<DrawType>,
+    /// The side that has lost on time
+    flagged_side: Option<Color>,
+    /// The side that has offered a draw which has not yet been accepted or declined
+    draw_offer: Option<Color>,
+    /// The time control of the game, if the game is timed
+    time_control: Option<TimeControl>,
+    /// The time remaining on each side's clock in the format [white, black]
+    time_remaining: [Duration; 2],
+    /// The time remaining on each side's clock before each move, for restoring the clocks when a move is undone
+    time_remaining_history: Vec<[Duration; 2]>,
 }
 
 impl Board {
     /// Constructs a `Board` from a `Fen` object.
+    /// Chess960 positions are detected automatically, since castling rights are stored as the squares of the castling rooks.
     pub fn from_fen(fen: Fen) -> Self {
         let (position, halfmove_clock, fullmove_number) = (fen.position().clone(), fen.halfmove_clock(), fen.fullmove_number());
         let mut board = Self {
             position,
             halfmove_clock,
             fullmove_number,
-            ongoing: halfmove_clock < 150,
+            ongoing: true,
             position_history: Vec::new(),
             move_history: Vec::new(),
             halfmove_clock_history: Vec::new(),
             initial_fen: fen,
             resigned_side: None,
-            draw_agreed: false,
+            draw_reason: None,
+            flagged_side: None,
+            draw_offer: None,
+            time_control: None,
+            time_remaining: [Duration::ZERO; 2],
+            time_remaining_history: Vec::new(),
         };
         board.update_status();
         board
     }
 
+    /// Constructs a `Board` from an EPD string, returning an error if it is invalid. The halfmove clock and fullmove number
+    /// are taken from the `hmvc` and `fmvn` operations if present, and other operations are discarded without being checked;
+    /// use [`Epd`](super::Epd) to access them.
+    pub fn from_epd(epd: &str) -> Result<Self, InvalidEpdError> {
+        Ok(Self::from_fen(Fen::from_epd(epd)?))
+    }
+
+    /// Returns the single legal move that transforms the position of `from` into that of `to`, or `None` if no legal move does
+    /// (or more than one does). The positions are compared as in [`Board::same_position`], and the move counters are not compared.
+    pub fn move_between(from: &Fen, to: &Fen) -> Option<Move> {
+        let target = to.position.with_normalized_ep();
+        let mut moves = from
+            .position
+            .gen_non_illegal_moves()
+            .into_iter()
+            .filter(|&m| from.position.with_move_made(m).unwrap().with_normalized_ep() == target);
+        match (moves.next(), moves.next()) {
+            (Some(move_), None) => Some(move_),
+            _ => None,
+        }
+    }
+
+    /// Constructs a `Board` from a UCI `position` command, i.e. `position startpos` or `position fen <FEN>`, optionally followed
+    /// by `moves` and a list of UCI moves which are played on the board. Returns an error if the command is malformed, the FEN is
+    /// invalid, or any move is invalid or illegal (in which case the error holds the first such move).
+    pub fn from_uci_position(cmd: &str) -> Result<Self, UciPositionError> {
+        let err = || UciPositionError::Command(cmd.to_owned());
+        let mut tokens = cmd.split_ascii_whitespace();
+        if tokens.next() != Some("position") {
+            return Err(err());
+        }
+        let mut board = match tokens.next() {
+            Some("startpos") => match tokens.next() {
+                None | Some("moves") => Board::default(),
+                _ => return Err(err()),
+            },
+            Some("fen") => {
+                let fields: Vec<_> = tokens.by_ref().take_while(|&token| token != "moves").collect();
+                Board::from_fen(Fen::try_from(fields.join(" ").as_str()).map_err(UciPositionError::Fen)?)
+            }
+            _ => return Err(err()),
+        };
+        for uci in tokens {
+            board.make_move_uci(uci).map_err(|_| UciPositionError::IllegalMove(uci.to_owned()))?;
+        }
+        Ok(board)
+    }
+
     /// Returns a `Fen` object representing the `Board`.
     pub fn to_fen(&self) -> Fen {
         Fen {
             position: self.position.clone(),
             halfmove_clock: self.halfmove_clock,
             fullmove_number: self.fullmove_number,
+            shredder: false,
         }
     }
 
@@ -64,6 +132,32 @@ impl Board {
         self.position.move_to_san(move_)
     }
 
+    /// Converts a `Move` to SAN without a trailing check or checkmate symbol, returning an error if the move is illegal.
+    pub fn move_to_san_plain(&self, move_: Move) -> Result<String, IllegalMoveError> {
+        let move_ = helpers::as_legal(move_, &self.gen_legal_moves()).ok_or(IllegalMoveError(move_))?;
+        self.position.move_to_san_plain(move_)
+    }
+
+    /// Converts a `Move` to long algebraic notation (see [`Position::move_to_lan`]), returning an error if the move is illegal.
+    pub fn move_to_lan(&self, move_: Move) -> Result<String, IllegalMoveError> {
+        let move_ = helpers::as_legal(move_, &self.gen_legal_moves()).ok_or(IllegalMoveError(move_))?;
+        self.position.move_to_lan(move_)
+    }
+
+    /// Converts a `Move` to UCI (see [`Position::move_to_uci`] for how castling is written), returning an error if the move is illegal.
+    pub fn move_to_uci(&self, move_: Move) -> Result<String, IllegalMoveError> {
+        let move_ = helpers::as_legal(self.position.resolve_king_onto_rook(move_), &self.gen_legal_moves()).ok_or(IllegalMoveError(move_))?;
+        self.position.move_to_uci(move_)
+    }
+
+    /// Constructs a `Move` from a long algebraic notation representation (see [`Position::lan_to_move`]), returning an error if it is invalid or illegal.
+    pub fn lan_to_move(&self, lan: &str) -> Result<Move, InvalidLanMoveError> {
+        if !self.ongoing {
+            return Err(InvalidLanMoveError(lan.to_owned()));
+        }
+        self.position.lan_to_move(lan)
+    }
+
     /// Constructs a `Move` from a SAN representation, returning an error if it is invalid or illegal.
     pub fn san_to_move(&self, san: &str) -> Result<Move, InvalidSanMoveError> {
         match self.position.san_to_move(san) {
@@ -78,7 +172,24 @@ impl Board {
         }
     }
 
+    /// Returns each legal move paired with its SAN, in the order described in [`Position::gen_non_illegal_moves`].
+    pub fn san_move_map(&self) -> Vec<(String, Move)> {
+        self.gen_legal_moves().into_iter().map(|m| (self.position.move_to_san(m).unwrap(), m)).collect()
+    }
+
+    /// Converts a move in SAN to UCI, returning an error if it is invalid or illegal in the current position.
+    pub fn san_to_uci(&self, san: &str) -> Result<String, InvalidSanMoveError> {
+        Ok(self.position.move_to_uci(self.san_to_move(san)?).unwrap())
+    }
+
+    /// Converts a move in UCI to SAN, returning an error if it is invalid or illegal in the current position.
+    pub fn uci_to_san(&self, uci: &str) -> Result<String, InvalidUciMoveError> {
+        let move_ = Move::from_uci(uci).map_err(|_| InvalidUciMoveError::InvalidUci(uci.to_owned()))?;
+        self.move_to_san(move_).map_err(|_| InvalidUciMoveError::IllegalMove(uci.to_owned()))
+    }
+
     /// Generates the legal moves in the position.
+    /// The moves are ordered as described in [`Position::gen_non_illegal_moves`].
     pub fn gen_legal_moves(&self) -> Vec<Move> {
         if self.ongoing {
             self.position.gen_non_illegal_moves()
@@ -87,9 +198,31 @@ impl Board {
         }
     }
 
-    /// Checks whether a move is legal in the position.
+    /// Counts the leaf nodes of the tree of legal moves of the given depth from the current position, for validating move generation
+    /// against known values. The game status and draw rules are ignored, and moves are made on copies of the position rather than the board.
+    pub fn perft(&self, depth: usize) -> u64 {
+        self.position.perft(depth)
+    }
+
+    /// Counts the leaf nodes of the tree of legal moves of the given depth under each legal move from the current position, as
+    /// in [`Board::perft`]. The counts are returned in move generation order, and sum to the perft value of the position.
+    pub fn perft_divide(&self, depth: usize) -> Vec<(Move, u64)> {
+        self.position
+            .gen_non_illegal_moves_uncached()
+            .into_iter()
+            .map(|m| (m, self.position.with_legal_move_made(m).perft(depth.saturating_sub(1))))
+            .collect()
+    }
+
+    /// Returns an iterator over the legal moves in the position, generating them lazily as with [`Position::legal_moves_iter`].
+    /// The iterator yields the same moves as [`Board::gen_legal_moves`], so it is empty if the game is over.
+    pub fn legal_moves_iter(&self) -> impl Iterator<Item = Move> + '_ {
+        self.ongoing.then(|| self.position.legal_moves_iter()).into_iter().flatten()
+    }
+
+    /// Checks whether a move is legal in the position, i.e. whether [`Board::make_move`] would accept it.
     pub fn is_legal(&self, move_: Move) -> bool {
-        helpers::as_legal(move_, &self.gen_legal_moves()).is_some()
+        self.ongoing && self.position.is_move_legal(self.position.resolve_king_onto_rook(move_))
     }
 
     /// Checks whether the given move is a capture, returning an error if the move is illegal.
@@ -101,29 +234,55 @@ impl Board {
     }
 
     /// Plays on the board the given move, returning an error if the move is illegal.
+    /// A king move onto one of its own castling rooks (as in Chess960 UCI, e.g. "e1h1") is played as castling.
     pub fn make_move(&mut self, move_: Move) -> Result<(), IllegalMoveError> {
-        let move_ = match helpers::as_legal(move_, &self.gen_legal_moves()) {
+        let move_ = match helpers::as_legal(self.position.resolve_king_onto_rook(move_), &self.gen_legal_moves()) {
             Some(m) => m,
             _ => return Err(IllegalMoveError(move_)),
         };
         let mut halfmove_clock = self.halfmove_clock;
         let fullmove_number = self.fullmove_number + if self.position.side.is_black() { 1 } else { 0 };
-        let Move(move_src, move_dest, ..) = move_;
+        let Move(move_src, move_dest, spec) = move_;
         let (moved_piece, dest_occ) = (self.position.content[move_src], self.position.content[move_dest]);
-        if matches!(moved_piece, Some(Piece(PieceType::P, _))) || dest_occ.is_some() {
+        let castling = matches!(spec, Some(SpecialMoveType::CastlingKingside | SpecialMoveType::CastlingQueenside));
+        if matches!(moved_piece, Some(Piece(PieceType::P, _))) || dest_occ.is_some() && !castling {
             halfmove_clock = 0;
         } else {
             halfmove_clock += 1;
         }
+        if self.draw_offer == Some(self.position.side) {
+            self.draw_offer = None;
+        }
         self.position_history.push(self.position.clone());
         self.position = self.position.with_move_made(move_).unwrap();
         self.move_history.push(move_);
         self.halfmove_clock_history.push(self.halfmove_clock);
+        self.time_remaining_history.push(self.time_remaining);
         (self.halfmove_clock, self.fullmove_number) = (halfmove_clock, fullmove_number);
         self.update_status();
         Ok(())
     }
 
+    /// Plays on the board the move from the square with index `from` to the square with index `to`, promoting to a queen if it
+    /// is a pawn move to the last rank, and returning an error if the move is illegal. Special moves other than promotions
+    /// (castling and en passant) are detected as with [`SpecialMoveType::Unclear`], so this suits moves entered by clicking squares.
+    pub fn make_move_default_promotion(&mut self, from: usize, to: usize) -> Result<(), IllegalMoveError> {
+        let queening = Move(from, to, Some(SpecialMoveType::Promotion(PieceType::Q)));
+        if self.is_legal(queening) {
+            self.make_move(queening)
+        } else {
+            self.make_move(Move(from, to, Some(SpecialMoveType::Unclear)))
+        }
+    }
+
+    /// Plays on the board the given move and returns its SAN representation (generated in the position before the move),
+    /// returning an error if the move is illegal.
+    pub fn make_move_logged(&mut self, move_: Move) -> Result<String, IllegalMoveError> {
+        let san = self.move_to_san(move_)?;
+        self.make_move(move_)?;
+        Ok(san)
+    }
+
     /// Attempts to parse the UCI representation of a move and play it on the board, returning an error if the move is invalid or illegal.
     pub fn make_move_uci(&mut self, uci: &str) -> Result<(), InvalidUciMoveError> {
         let move_ = Move::from_uci(uci).map_err(|_| InvalidUciMoveError::InvalidUci(uci.to_owned()))?;
@@ -160,27 +319,171 @@ impl Board {
         Ok(())
     }
 
-    /// Undoes the most recent move, returning an error if no moves have been played.
+    /// Attempts to play the given moves on the board, calling `cb` with the board and the move just played after each move,
+    /// and returning an error if any move is illegal. If an error is returned, the board is left unchanged, i.e. no moves are
+    /// played on the board, although `cb` will already have been called for the moves before the illegal one.
+    pub fn make_moves_with<F: FnMut(&Board, Move)>(&mut self, moves: &[Move], mut cb: F) -> Result<(), IllegalMoveError> {
+        let mut board = self.clone();
+        for &move_ in moves {
+            board.make_move(move_)?;
+            cb(&board, move_);
+        }
+        *self = board;
+        Ok(())
+    }
+
+    /// Attempts to play the moves in the given PGN movetext on the board, returning an error if any move is invalid or illegal.
+    /// Unlike [`Board::make_moves_san`], the movetext may include move numbers, comments, variations, NAGs, move annotations
+    /// (like '!?') and a trailing result token, all of which are ignored. If an error is returned, the board is left unchanged.
+    pub fn play_movetext(&mut self, text: &str) -> Result<(), InvalidSanMoveError> {
+        let mut board = self.clone();
+        for san in helpers::movetext_sans(text) {
+            board.make_move_san(&san)?;
+        }
+        *self = board;
+        Ok(())
+    }
+
+    /// Plays out captures on a copy of the board until the position is quiet, returning the resulting board.
+    ///
+    /// On each iteration, `evaluator` is asked to choose a move in the current position. If the side to move has
+    /// no legal captures, or the chosen move is not a legal capture, the loop stops and the board reached so far is
+    /// returned. This is useful for demonstrating quiescence-style stabilization of a position with the move API.
+    pub fn resolve_captures(&self, evaluator: impl Fn(&Board) -> Move) -> Board {
+        let mut board = self.clone();
+        while board.gen_legal_moves().into_iter().any(|m| board.is_capture(m).unwrap()) {
+            let move_ = evaluator(&board);
+            if !matches!(board.is_capture(move_), Ok(true)) {
+                break;
+            }
+            board.make_move(move_).unwrap();
+        }
+        board
+    }
+
+    /// Returns an independent copy of the board for exploring an alternative line. Moves played on the branch do
+    /// not affect the original board, which makes this the intended mechanism for building analysis trees.
+    pub fn branch(&self) -> Board {
+        self.clone()
+    }
+
+    /// Returns the index of the first ply at which the move histories of two boards differ (`None` if they are identical).
+    /// If one history is a prefix of the other, the boards diverge at the ply following the shorter history, and boards
+    /// with different initial positions diverge at ply 0.
+    pub fn diverges_at(&self, other: &Board) -> Option<usize> {
+        if self.initial_fen != other.initial_fen {
+            return Some(0);
+        }
+        match self.move_history.iter().zip(&other.move_history).position(|(a, b)| a != b) {
+            Some(ply) => Some(ply),
+            None if self.move_history.len() != other.move_history.len() => Some(std::cmp::min(self.move_history.len(), other.move_history.len())),
+            None => None,
+        }
+    }
+
+    /// Checks whether two boards are at the same position, i.e. the same piece placement, side to move, castling rights and
+    /// en passant target square, which is useful for detecting transpositions between differently played games.
+    /// Unlike the derived `PartialEq` implementation, which compares entire games including their histories, this
+    /// function ignores how the position was reached, as well as an en passant target square when no en passant
+    /// capture is possible. If `compare_clocks` is `true`, the halfmove clocks and fullmove numbers must also be equal.
+    pub fn same_position(&self, other: &Board, compare_clocks: bool) -> bool {
+        self.position.with_normalized_ep() == other.position.with_normalized_ep() && (!compare_clocks || self.halfmove_clock == other.halfmove_clock && self.fullmove_number == other.fullmove_number)
+    }
+
+    /// Returns a 64-bit hash of the current game state: the [Zobrist hash](Position::zobrist_hash) of the position mixed with the
+    /// halfmove clock and fullmove number. Unlike the derived `Hash` implementation, which also hashes the move and position
+    /// histories (and the game status), this ignores how the position was reached, so boards that transpose into the same state
+    /// have the same hash. Like the Zobrist hash, it is stable within a major release.
+    pub fn state_hash(&self) -> u64 {
+        self.position.zobrist_hash() ^ zobrist::mix((self.halfmove_clock as u64) << 32 | self.fullmove_number as u64)
+    }
+
+    /// Undoes the most recent move and returns it, or returns an error if no moves have been played.
+    /// In a timed game, both sides' clocks are restored to the time remaining before the move was played.
     /// Note that if the game had ended, calling this function sets the game to ongoing again.
     /// This will override any resignation or draw by agreement.
-    pub fn undo_move(&mut self) -> Result<(), NoMovesPlayedError> {
-        if self.move_history.is_empty() {
-            return Err(NoMovesPlayedError);
-        }
+    pub fn undo_move(&mut self) -> Result<Move, NoMovesPlayedError> {
+        let move_ = self.move_history.pop().ok_or(NoMovesPlayedError)?;
         self.fullmove_number -= if self.side_to_move().is_white() { 1 } else { 0 };
-        self.move_history.pop();
         self.position = self.position_history.pop().unwrap();
         self.halfmove_clock = self.halfmove_clock_history.pop().unwrap();
+        self.time_remaining = self.time_remaining_history.pop().unwrap();
         self.ongoing = true;
         self.resigned_side = None;
-        self.draw_agreed = false;
+        self.draw_reason = None;
+        self.flagged_side = None;
+        self.draw_offer = None;
+        Ok(move_)
+    }
+
+    /// Changes the side to move, clearing the en passant target square, and returns an error if the resulting position is illegal
+    /// (e.g. if the side not to move would be in check), in which case the board is left unchanged. Unless the game has ended
+    /// by resignation, agreement, a draw claim or timeout, the status of the game is then re-evaluated for the new side to move, so that
+    /// passing the move in a stalemate or checkmate position resumes the game if the new side to move has legal moves.
+    pub fn set_side_to_move(&mut self, color: Color) -> Result<(), InvalidPositionError> {
+        let position = Position {
+            side: color,
+            ep_target: None,
+            ..self.position.clone()
+        };
+        let problems = position.validate();
+        if !problems.is_empty() {
+            return Err(InvalidPositionError(problems));
+        }
+        self.position = position;
+        let draw_by_players = matches!(self.draw_reason, Some(DrawType::Agreement | DrawType::ThreefoldRepetition | DrawType::FiftyMoveRule));
+        if self.resigned_side.is_none() && self.flagged_side.is_none() && !draw_by_players {
+            self.ongoing = true;
+            self.draw_reason = None;
+        }
+        self.update_status();
         Ok(())
     }
 
-    /// Updates the `ongoing` property of the `Board` if the game is over.
+    /// Returns the game mirrored vertically with the colors swapped, so that white's moves become black's and vice versa.
+    /// The initial position and every move played are flipped, and a resignation, loss on time, draw by agreement or claimed draw is carried over
+    /// (with the winner's color flipped). This is useful for augmenting training data with symmetric games.
+    pub fn color_flipped(&self) -> Board {
+        let initial_fen = Fen {
+            position: self.initial_fen.position.color_flipped(),
+            ..self.initial_fen.clone()
+        };
+        let mut board = Board::from_fen(initial_fen);
+        for &Move(src, dest, spec) in &self.move_history {
+            board.make_move(Move(src ^ 56, dest ^ 56, spec)).unwrap();
+        }
+        if let Some(side) = self.resigned_side {
+            board.resign(!side).unwrap();
+        } else if let Some(side) = self.flagged_side {
+            board.flag(!side).unwrap();
+        } else if self.draw_agreed() {
+            board.agree_draw().unwrap();
+        } else if matches!(self.draw_reason, Some(DrawType::ThreefoldRepetition | DrawType::FiftyMoveRule)) {
+            board.claim_draw().unwrap();
+        }
+        board
+    }
+
+    /// Updates the `ongoing` property of the `Board` if the game is over, recording the reason if the game has been drawn.
     fn update_status(&mut self) {
-        if self.is_fivefold_repetition() || self.is_seventy_five_move_rule() || self.is_stalemate() || self.is_insufficient_material() || self.is_checkmate() {
+        if self.is_checkmate() {
+            self.ongoing = false;
+            return;
+        }
+        let draw_reason = if let Some(s) = self.stalemated_side() {
+            Some(DrawType::Stalemate(s))
+        } else if self.is_fivefold_repetition() {
+            Some(DrawType::FivefoldRepetition)
+        } else if self.is_seventy_five_move_rule() {
+            Some(DrawType::SeventyFiveMoveRule)
+        } else if self.is_insufficient_material() {
+            Some(DrawType::InsufficientMaterial)
+        } else {
+            None
+        };
+        if draw_reason.is_some() {
             self.ongoing = false;
+            self.draw_reason = draw_reason;
         }
     }
 
@@ -199,55 +502,96 @@ impl Board {
         if self.ongoing {
             None
         } else {
-            Some(if self.draw_agreed {
-                GameResult::Draw(DrawType::Agreement)
+            Some(if let Some(reason) = self.draw_reason {
+                GameResult::Draw(reason)
             } else if let Some(s) = self.resigned_side {
                 GameResult::Wins(!s, WinType::Resignation)
+            } else if let Some(s) = self.flagged_side {
+                GameResult::Wins(!s, WinType::Timeout)
             } else {
                 match self.checkmated_side() {
                     Some(Color::Black) => GameResult::Wins(Color::White, WinType::Checkmate),
                     Some(Color::White) => GameResult::Wins(Color::Black, WinType::Checkmate),
-                    None => {
-                        if let Some(s) = self.stalemated_side() {
-                            GameResult::Draw(DrawType::Stalemate(s))
-                        } else if self.is_fivefold_repetition() {
-                            GameResult::Draw(DrawType::FivefoldRepetition)
-                        } else if self.is_seventy_five_move_rule() {
-                            GameResult::Draw(DrawType::SeventyFiveMoveRule)
-                        } else if self.is_insufficient_material() {
-                            GameResult::Draw(DrawType::InsufficientMaterial)
-                        } else {
-                            panic!("the universe is malfunctioning")
-                        }
-                    }
+                    None => panic!("the universe is malfunctioning"),
                 }
             })
         }
     }
 
+    /// Returns the way the game has ended, or `Termination::Ongoing` if it is still ongoing.
+    pub fn termination(&self) -> Termination {
+        match self.game_result() {
+            None => Termination::Ongoing,
+            Some(GameResult::Wins(winner, WinType::Checkmate)) => Termination::Checkmate(!winner),
+            Some(GameResult::Wins(winner, WinType::Resignation)) => Termination::Resignation(!winner),
+            Some(GameResult::Wins(winner, WinType::Timeout)) => Termination::Timeout(!winner),
+            Some(GameResult::Draw(DrawType::Stalemate(s))) => Termination::Stalemate(s),
+            Some(GameResult::Draw(DrawType::InsufficientMaterial)) => Termination::InsufficientMaterial,
+            Some(GameResult::Draw(DrawType::SeventyFiveMoveRule)) => Termination::SeventyFiveMove,
+            Some(GameResult::Draw(DrawType::FivefoldRepetition)) => Termination::FivefoldRepetition,
+            Some(GameResult::Draw(DrawType::FiftyMoveRule)) => Termination::FiftyMove,
+            Some(GameResult::Draw(DrawType::ThreefoldRepetition)) => Termination::ThreefoldRepetition,
+            Some(GameResult::Draw(DrawType::Agreement)) => Termination::Agreement,
+            Some(GameResult::Draw(DrawType::TimeoutVsInsufficientMaterial)) => Termination::TimeoutVsInsufficientMaterial(self.flagged_side.unwrap()),
+        }
+    }
+
     /// Returns the number of halfmoves played since the last pawn push or capture.
     pub fn halfmove_clock(&self) -> usize {
         self.halfmove_clock
     }
 
+    /// Returns the number of plies played since the last pawn move or capture, which is the [halfmove clock](Board::halfmove_clock).
+    pub fn plies_since_last_capture_or_pawn_move(&self) -> usize {
+        self.halfmove_clock
+    }
+
+    /// Returns the index in the move history of the last irreversible move (a capture, pawn move or castling move) played
+    /// on the board, or `None` if no such move has been played. No position before this ply can be repeated.
+    pub fn last_irreversible_ply(&self) -> Option<usize> {
+        (0..self.move_history.len()).rev().find(|&i| {
+            let (position, move_) = (&self.position_history[i], self.move_history[i]);
+            matches!(position.content[move_.0], Some(Piece(PieceType::P, _)))
+                || matches!(move_.2, Some(SpecialMoveType::CastlingKingside | SpecialMoveType::CastlingQueenside))
+                || position.is_capture(move_).unwrap()
+        })
+    }
+
     /// Returns the fullmove number.
     pub fn fullmove_number(&self) -> usize {
         self.fullmove_number
     }
 
-    /// Checks whether a threefold repetition of the position has occurred.
+    /// Returns the number of times the current position has occurred in the game, including its present occurrence.
+    /// Positions are the same if they have the same piece placement, side to move, castling rights and en passant possibility;
+    /// an en passant target square only counts if a pawn can actually capture onto it.
+    fn repetition_count(&self) -> usize {
+        let position = self.position.with_normalized_ep();
+        self.position_history.iter().filter(|&pos| pos.with_normalized_ep() == position).count() + 1
+    }
+
+    /// Checks whether the current position has occurred at least three times (counting its present occurrence).
     pub fn is_threefold_repetition(&self) -> bool {
-        self.position_history.iter().fold(0, |acc, pos| if pos == &self.position { acc + 1 } else { acc }) == 3
+        self.repetition_count() >= 3
+    }
+
+    /// Checks whether playing the given move would make the resulting position occur for at least the third time,
+    /// returning an error if the move is illegal.
+    pub fn move_causes_threefold(&self, move_: Move) -> Result<bool, IllegalMoveError> {
+        let mut board = self.clone();
+        board.make_move(move_)?;
+        Ok(board.is_threefold_repetition())
     }
 
-    /// Checks whether a fivefold repetition of the position has occurred.
+    /// Checks whether the current position has occurred at least five times (counting its present occurrence).
     pub fn is_fivefold_repetition(&self) -> bool {
-        self.position_history.iter().fold(0, |acc, pos| if pos == &self.position { acc + 1 } else { acc }) == 5
+        self.repetition_count() >= 5
     }
 
-    /// Checks whether a draw can be claimed by the fifty-move rule.
+    /// Checks whether a draw can be claimed by the fifty-move rule, i.e. whether at least fifty moves by each side have been
+    /// played without a pawn move or capture.
     pub fn is_fifty_move_rule(&self) -> bool {
-        self.halfmove_clock == 100
+        self.halfmove_clock >= 100
     }
 
     /// Checks whether the game is drawn by the seventy-five-move rule.
@@ -255,20 +599,78 @@ impl Board {
         self.halfmove_clock == 150
     }
 
+    /// Returns the number of halfmoves that must still be played without a pawn move or capture before a draw can be claimed
+    /// by the fifty-move rule, i.e. `100` minus the halfmove clock, or 0 if a draw can already be claimed.
+    pub fn moves_until_fifty_move_rule(&self) -> usize {
+        100usize.saturating_sub(self.halfmove_clock)
+    }
+
+    /// Returns the number of halfmoves that must still be played without a pawn move or capture before the game is drawn
+    /// by the seventy-five-move rule, i.e. `150` minus the halfmove clock, or 0 if the game has already been drawn by it.
+    pub fn moves_until_seventy_five_move_rule(&self) -> usize {
+        150usize.saturating_sub(self.halfmove_clock)
+    }
+
     /// Checks whether the game is drawn by stalemate. Use [`Board::stalemated_side`] to know which side is in stalemate.
     pub fn is_stalemate(&self) -> bool {
         self.position.is_stalemate()
     }
 
+    /// Returns the first move in the opening book for the current position that is legal on the board (`None` if the position is out of book).
+    pub fn book_move(&self, book: &OpeningBook) -> Option<Move> {
+        book.moves(&self.position).iter().find_map(|&m| helpers::as_legal(m, &self.gen_legal_moves()))
+    }
+
+    /// Checks whether the given move is in the opening book for the current position.
+    pub fn is_book_move(&self, book: &OpeningBook, move_: Move) -> bool {
+        let legal_moves = self.gen_legal_moves();
+        let Some(move_) = helpers::as_legal(move_, &legal_moves) else {
+            return false;
+        };
+        book.moves(&self.position).iter().any(|&m| helpers::as_legal(m, &legal_moves) == Some(move_))
+    }
+
+    /// Returns the legal moves that checkmate the opponent, including underpromotions, or an empty vector if there are none.
+    pub fn mating_moves(&self) -> Vec<Move> {
+        self.gen_legal_moves().into_iter().filter(|&m| self.position.with_move_made(m).unwrap().is_checkmate()).collect()
+    }
+
+    /// Checks whether the only way to checkmate the opponent in one move is an underpromotion (a promotion to a rook, bishop or knight).
+    pub fn requires_underpromotion(&self) -> bool {
+        let mating_moves = self.mating_moves();
+        !mating_moves.is_empty() && mating_moves.iter().all(|m| matches!(m.2, Some(SpecialMoveType::Promotion(pt)) if pt != PieceType::Q))
+    }
+
+    /// Returns the legal moves that would leave the opponent in stalemate, or an empty vector if there are none.
+    /// This is useful for warning a winning side against stalemating the opponent, or for finding a saving stalemate.
+    pub fn stalemate_risk(&self) -> Vec<Move> {
+        self.gen_legal_moves().into_iter().filter(|&m| self.position.with_move_made(m).unwrap().is_stalemate()).collect()
+    }
+
     /// Checks whether the game is drawn by insufficient material.
     ///
     /// rschess defines insufficient material as any of the following scenarios:
     /// * King and knight vs. king
     /// * King and zero or more bishops vs. king and zero or more bishops where all the bishops are on the same color complex
+    ///
+    /// In these positions no sequence of legal moves can lead to checkmate, so the game is drawn automatically.
+    /// Positions where checkmate is possible but cannot be forced, such as king and two knights vs. king, are
+    /// **not** considered insufficient material; use [`Board::cannot_force_mate`] to detect those.
     pub fn is_insufficient_material(&self) -> bool {
         self.position.is_insufficient_material()
     }
 
+    /// Checks whether neither side can force checkmate against best defence.
+    /// This is not an automatic draw, since checkmate may still be possible with the cooperation of the losing side.
+    ///
+    /// rschess considers checkmate impossible to force in any of the following scenarios:
+    /// * Any scenario of insufficient material (see [`Board::is_insufficient_material`])
+    /// * King and two knights vs. king
+    /// * King and at most one knight or bishop vs. king and at most one knight or bishop
+    pub fn cannot_force_mate(&self) -> bool {
+        self.position.cannot_force_mate()
+    }
+
     /// Checks whether there is sufficient checkmating material on the board.
     pub fn is_sufficient_material(&self) -> bool {
         !self.is_insufficient_material()
@@ -306,17 +708,30 @@ impl Board {
         self.position.pretty_print(perspective, ascii)
     }
 
+    /// Pretty-prints the position to a string, from the perspective of the side `perspective`, using the given `PrintOptions`.
+    /// The default options produce the same output as [`Board::pretty_print`] with `ascii` set to `false`.
+    pub fn pretty_print_opts(&self, perspective: Color, options: PrintOptions) -> String {
+        self.position.pretty_print_opts(perspective, options)
+    }
+
     /// Returns which side's turn it is to move.
     pub fn side_to_move(&self) -> Color {
         self.position.side
     }
 
+    /// Returns the legal moves of the piece on the given square, for showing move hints (an empty vector if the square is empty,
+    /// the piece cannot move or the game is over), or an error if the square name is invalid. See [`Position::legal_moves_from`].
+    pub fn legal_moves_from(&self, file: char, rank: char) -> Result<Vec<Move>, InvalidSquareNameError> {
+        let sq = super::sq_to_idx(file, rank)?;
+        Ok(if self.ongoing { self.position.legal_moves_from(sq) } else { Vec::new() })
+    }
+
     /// Returns the occupant of a square, or an error if the square name is invalid.
     pub fn occupant_of_square(&self, file: char, rank: char) -> Result<Option<Piece>, InvalidSquareNameError> {
         Ok(self.position.content[super::sq_to_idx(file, rank)?])
     }
 
-    /// Resigns the game for a certain side, if the game is ongoing. Currently, this function should also be used to represent a loss by timeout.
+    /// Resigns the game for a certain side, if the game is ongoing. Use [`Board::flag`] to represent a loss on time.
     pub fn resign(&mut self, side: Color) -> Result<(), GameOverError> {
         if !self.ongoing {
             return Err(GameOverError::Resignation);
@@ -326,16 +741,150 @@ impl Board {
         Ok(())
     }
 
-    /// Makes a draw by agreement, if the game is ongoing. Currently, this function should also be used to represent a draw claim.
+    /// Makes a draw by agreement, if the game is ongoing. Use [`Board::claim_draw`] to represent a draw claim.
     pub fn agree_draw(&mut self) -> Result<(), GameOverError> {
         if !self.ongoing {
             return Err(GameOverError::AgreementDraw);
         }
         self.ongoing = false;
-        self.draw_agreed = true;
+        self.draw_reason = Some(DrawType::Agreement);
         Ok(())
     }
 
+    /// Records a draw offer by the side `by`, if the game is ongoing, replacing any pending offer. The offer stands until it is
+    /// accepted with [`Board::accept_draw`], declined with [`Board::decline_draw`], or withdrawn by the offering side playing a move.
+    pub fn offer_draw(&mut self, by: Color) -> Result<(), GameOverError> {
+        if !self.ongoing {
+            return Err(GameOverError::DrawOffer);
+        }
+        self.draw_offer = Some(by);
+        Ok(())
+    }
+
+    /// Returns the side whose draw offer is pending (`None` if there is no pending offer or the game is over).
+    pub fn pending_draw_offer(&self) -> Option<Color> {
+        self.draw_offer.filter(|_| self.ongoing)
+    }
+
+    /// Accepts the pending draw offer, making a draw by agreement. Returns an error if the game is over or no draw has been offered.
+    pub fn accept_draw(&mut self) -> Result<(), GameOverError> {
+        if self.pending_draw_offer().is_none() {
+            return Err(if self.ongoing { GameOverError::NoDrawOffer } else { GameOverError::AgreementDraw });
+        }
+        self.draw_offer = None;
+        self.agree_draw()
+    }
+
+    /// Declines the pending draw offer, if there is one.
+    pub fn decline_draw(&mut self) {
+        self.draw_offer = None;
+    }
+
+    /// Returns the type of draw that the side to move could claim in the current position (threefold repetition or the
+    /// fifty-move rule, preferring the former if both apply), or `None` if the game is over or there is no basis for a claim.
+    pub fn can_claim_draw(&self) -> Option<DrawType> {
+        if !self.ongoing {
+            None
+        } else if self.is_threefold_repetition() {
+            Some(DrawType::ThreefoldRepetition)
+        } else if self.is_fifty_move_rule() {
+            Some(DrawType::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    /// Ends the game in a draw claimed by the side to move, if the game is ongoing and a claim is valid in the current position
+    /// (see [`Board::can_claim_draw`]). Unlike [`Board::agree_draw`], this returns an error if there is no basis for the claim.
+    pub fn claim_draw(&mut self) -> Result<(), GameOverError> {
+        if !self.ongoing {
+            return Err(GameOverError::DrawClaim);
+        }
+        let reason = self.can_claim_draw().ok_or(GameOverError::NoDrawToClaim)?;
+        self.ongoing = false;
+        self.draw_reason = Some(reason);
+        Ok(())
+    }
+
+    /// Ends the game as a loss on time for a certain side, if the game is ongoing. If the other side could not checkmate by any
+    /// series of legal moves (e.g. it only has a king, or a king and a knight against a lone king), the game is drawn instead,
+    /// which is reported as [`DrawType::TimeoutVsInsufficientMaterial`].
+    pub fn flag(&mut self, side: Color) -> Result<(), GameOverError> {
+        if !self.ongoing {
+            return Err(GameOverError::Timeout);
+        }
+        self.ongoing = false;
+        self.flagged_side = Some(side);
+        if !self.position.could_checkmate(!side) {
+            self.draw_reason = Some(DrawType::TimeoutVsInsufficientMaterial);
+        }
+        Ok(())
+    }
+
+    /// Returns an optional `Color` representing the side that has lost on time (`None` if neither side has lost on time).
+    pub fn flagged_side(&self) -> Option<Color> {
+        self.flagged_side
+    }
+
+    /// Sets the time control of the game, resetting both sides' clocks to the initial time.
+    /// The clocks are also reset to the initial time for every move already played, to which they return if those moves are undone.
+    pub fn set_time_control(&mut self, time_control: TimeControl) {
+        self.time_control = Some(time_control);
+        self.time_remaining = [time_control.initial; 2];
+        self.time_remaining_history.fill([time_control.initial; 2]);
+    }
+
+    /// Sets the clock of the game, replacing the time remaining on both sides' clocks and the increment. The time control's
+    /// delay (and initial time) are kept if the game is already timed; otherwise the game becomes timed with no delay, and the
+    /// larger of the two remaining times as the initial time. The clocks return to the given times if moves already played are undone.
+    pub fn set_clock(&mut self, clock: Clock) {
+        let Clock { white, black, increment } = clock;
+        let time_control = self.time_control.unwrap_or(TimeControl::new(white.max(black), increment));
+        self.time_control = Some(TimeControl { increment, ..time_control });
+        self.time_remaining = [white, black];
+        self.time_remaining_history.fill([white, black]);
+    }
+
+    /// Returns the current state of the game's clock (`None` if the game is not timed).
+    pub fn clock(&self) -> Option<Clock> {
+        self.time_control.map(|time_control| Clock {
+            white: self.time_remaining[Color::White.index()],
+            black: self.time_remaining[Color::Black.index()],
+            increment: time_control.increment,
+        })
+    }
+
+    /// Returns the time control of the game (`None` if the game is not timed).
+    pub fn time_control(&self) -> Option<TimeControl> {
+        self.time_control
+    }
+
+    /// Returns the time remaining on the clock of the side `color` (`None` if the game is not timed).
+    pub fn time_remaining(&self, color: Color) -> Option<Duration> {
+        self.time_control.map(|_| self.time_remaining[color.index()])
+    }
+
+    /// Records the time taken by the side that has just moved, and should be called after every move in a timed game.
+    /// Time beyond the delay is deducted from that side's clock, after which the increment is added. If the side runs
+    /// out of time and the game is still ongoing, the game ends with [`Board::flag`]. This function does nothing if the
+    /// game is not timed or no moves have been played.
+    pub fn on_move_played(&mut self, elapsed: Duration) {
+        let (Some(time_control), false) = (self.time_control, self.move_history.is_empty()) else {
+            return;
+        };
+        let side = !self.side_to_move();
+        let remaining = &mut self.time_remaining[side.index()];
+        let charged = elapsed.saturating_sub(time_control.delay);
+        if charged >= *remaining {
+            *remaining = Duration::ZERO;
+            if self.ongoing {
+                self.flag(side).unwrap();
+            }
+        } else {
+            *remaining = *remaining - charged + time_control.increment;
+        }
+    }
+
     /// Returns an optional `Color` representing the side that has resigned (`None` if neither side has resigned).
     pub fn resigned_side(&self) -> Option<Color> {
         self.resigned_side
@@ -343,7 +892,7 @@ impl Board {
 
     /// Checks whether a draw has been agreed upon.
     pub fn draw_agreed(&self) -> bool {
-        self.draw_agreed
+        self.draw_reason == Some(DrawType::Agreement)
     }
 
     /// Returns the initial FEN of the game.
@@ -372,10 +921,250 @@ impl Board {
         movetext.trim().to_owned()
     }
 
+    /// Returns the FEN of every position of the game, starting with the initial position and ending with the current one.
+    pub fn fen_history(&self) -> Vec<Fen> {
+        let mut fullmove_number = self.initial_fen.fullmove_number();
+        let mut fens = Vec::new();
+        for (position, &halfmove_clock) in self.position_history.iter().zip(&self.halfmove_clock_history) {
+            fens.push(Fen {
+                position: position.clone(),
+                halfmove_clock,
+                fullmove_number,
+                shredder: false,
+            });
+            fullmove_number += if position.side.is_black() { 1 } else { 0 };
+        }
+        fens.push(self.to_fen());
+        fens
+    }
+
+    /// Generates movetext like [`Board::gen_movetext`], with the FEN of the position after each move in a comment following it,
+    /// e.g. "1. e4 {rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1} e5 {...}". This is mainly useful for debugging.
+    pub fn movetext_with_fen_comments(&self) -> String {
+        self.movetext_with_comments(|fen| fen.to_string())
+    }
+
+    /// Generates movetext like [`Board::gen_movetext`], with a comment after each move generated from the FEN of the position after it.
+    fn movetext_with_comments(&self, comment: impl Fn(&Fen) -> String) -> String {
+        let fens = self.fen_history();
+        let mut movetext = String::new();
+        for (movei, (pos, &move_)) in self.position_history.iter().zip(&self.move_history).enumerate() {
+            let san = pos.move_to_san(move_).unwrap();
+            let fullmove_number = fens[movei].fullmove_number;
+            if pos.side.is_white() {
+                movetext.push_str(&format!("{fullmove_number}. "));
+            } else if movei == 0 {
+                movetext.push_str(&format!("{fullmove_number}... "));
+            }
+            movetext.push_str(&format!("{san} {{{}}} ", comment(&fens[movei + 1])));
+        }
+        movetext.trim().to_owned()
+    }
+
+    /// Heuristically checks whether the side `color` is clearly winning, for quick adjudication by simple bots.
+    /// If the game is over, this is whether `color` has won. Otherwise, the material balance in centipawns (100 per pawn)
+    /// must favor `color` by at least `margin`, and the position must not be one in which checkmate cannot be forced
+    /// (see [`Board::cannot_force_mate`]). Positional factors are not taken into account.
+    pub fn is_clearly_winning(&self, color: Color, margin: i32) -> bool {
+        if let Some(result) = self.game_result() {
+            return matches!(result, GameResult::Wins(winner, _) if winner == color);
+        }
+        let balance = self.position.material_balance() * 100;
+        (if color.is_white() { balance } else { -balance }) >= margin && !self.cannot_force_mate()
+    }
+
+    /// Checks whether the material is roughly equal but the sides have different pieces, e.g. two minor pieces against a rook
+    /// and a pawn, or a bishop against a knight. The material is considered roughly equal if the [balance](Position::material_balance)
+    /// is at most one pawn either way, and the compositions are compared by the [count](Position::material_count) of each type of piece other than pawns.
+    pub fn has_material_imbalance(&self) -> bool {
+        let (white, black) = (self.position.material_count(Color::White), self.position.material_count(Color::Black));
+        self.position.material_balance().abs() <= 1 && [PieceType::Q, PieceType::R, PieceType::B, PieceType::N].into_iter().any(|pt| white.count(pt) != black.count(pt))
+    }
+
+    /// Returns the [material balance](Position::material_balance) before each ply of the game, followed by that of the current position.
+    pub fn material_balance_history(&self) -> Vec<i32> {
+        self.position_history.iter().chain([&self.position]).map(|p| p.material_balance()).collect()
+    }
+
+    /// Returns an iterator over the moves played thus far, each paired with the position that occurred after it, for stepping through the game.
+    pub fn replay(&self) -> impl Iterator<Item = (Move, Position)> + '_ {
+        self.move_history.iter().copied().zip(self.position_history.iter().skip(1).chain([&self.position]).cloned())
+    }
+
+    /// Returns the UCI representation of each move played thus far, in order, as would be sent to an engine.
+    /// Castling is written as described in [`Position::move_to_uci`].
+    pub fn moves_uci(&self) -> Vec<String> {
+        self.move_history.iter().zip(&self.position_history).map(|(&m, p)| p.move_to_uci(m).unwrap()).collect()
+    }
+
+    /// Groups the SAN moves of the game thus far into rows of `(fullmove number, white's move, black's move)`.
+    /// White's move is `None` in the first row if the game started with black to move, and black's move is `None`
+    /// in the last row if white made the last move.
+    pub fn movetext_rows(&self) -> Vec<(usize, Option<String>, Option<String>)> {
+        let mut rows: Vec<(usize, Option<String>, Option<String>)> = Vec::new();
+        let mut fullmove_number = self.initial_fen.fullmove_number();
+        for (pos, &move_) in self.position_history.iter().zip(&self.move_history) {
+            let san = pos.move_to_san(move_).unwrap();
+            if pos.side.is_white() {
+                rows.push((fullmove_number, Some(san), None));
+            } else {
+                match rows.last_mut() {
+                    Some(row) if row.0 == fullmove_number => row.2 = Some(san),
+                    _ => rows.push((fullmove_number, None, Some(san))),
+                }
+                fullmove_number += 1;
+            }
+        }
+        rows
+    }
+
+    /// Like [`Board::movetext_rows`], but with the piece letters in each move replaced by figurine glyphs
+    /// (e.g. '♘f3' and '♞c6' instead of 'Nf3' and 'Nc6'), suitable for printing a traditional scoresheet.
+    pub fn scoresheet(&self) -> Vec<(usize, Option<String>, Option<String>)> {
+        let figurine = |san: String, color| {
+            san.chars()
+                .map(|c| match PieceType::try_from(c) {
+                    Ok(piece_type) if c.is_ascii_uppercase() && piece_type != PieceType::P => Piece(piece_type, color).to_string(),
+                    _ => c.to_string(),
+                })
+                .collect()
+        };
+        self.movetext_rows()
+            .into_iter()
+            .map(|(n, white, black)| (n, white.map(|san| figurine(san, Color::White)), black.map(|san| figurine(san, Color::Black))))
+            .collect()
+    }
+
+    /// Generates a complete PGN document of the game thus far, with the given tags, a _FEN_ and _SetUp_ tag pair if the game
+    /// did not start from the standard starting position, the movetext and the result ("*" if the game is still ongoing).
+    /// The movetext is wrapped so that no line is longer than 80 characters.
+    pub fn to_pgn(&self, tags: &PgnTags) -> String {
+        self.pgn_with_movetext(tags, self.gen_movetext())
+    }
+
+    /// Generates a PGN document like [`Board::to_pgn`], annotating each move with the evaluation of the position after it,
+    /// as computed by `eval` in centipawns from white's perspective, in the `[%eval]` comment format (e.g. "{[%eval 0.35]}").
+    /// Only the moves played on the board (i.e. the mainline) are annotated: variations cannot be exported, since the board
+    /// does not store them and the crate has no game tree type.
+    pub fn to_pgn_with_evals(&self, tags: &PgnTags, eval: impl Fn(&Position) -> i32) -> String {
+        let movetext = self.movetext_with_comments(|fen| {
+            let centipawns = eval(fen.position());
+            format!(
+                "[%eval {}{}.{:02}]",
+                if centipawns < 0 { "-" } else { "" },
+                centipawns.unsigned_abs() / 100,
+                centipawns.unsigned_abs() % 100
+            )
+        });
+        self.pgn_with_movetext(tags, movetext)
+    }
+
+    /// Generates a PGN document of the game with the given tags and movetext, as described in [`Board::to_pgn`].
+    fn pgn_with_movetext(&self, tags: &PgnTags, movetext: String) -> String {
+        let result = match self.game_result() {
+            Some(res) => res.to_string(),
+            None => "*".to_owned(),
+        };
+        let mut tag_pairs = tags.tag_pairs(Some(&result));
+        if self.initial_fen != Board::default().initial_fen {
+            tag_pairs.push(("SetUp".to_owned(), "1".to_owned()));
+            tag_pairs.push(("FEN".to_owned(), self.initial_fen.to_string()));
+        }
+        let mut pgn: String = tag_pairs.iter().map(|(name, value)| PgnTags::format_tag_pair(name, value) + "\n").collect();
+        pgn.push('\n');
+        pgn.push_str(&helpers::wrap_words(&[movetext, result].join(" "), 80));
+        pgn
+    }
+
+    /// Constructs a `Board` from a PGN document, replaying its movetext from the position in the _FEN_ tag (or the standard
+    /// starting position if there is none). Move numbers, comments, variations, NAGs and move annotations are skipped.
+    /// A decisive or drawn result (from the movetext, or else the _Result_ tag) that is not reached on the board is
+    /// recorded as a resignation or a draw by agreement; a result contradicting the board is an error.
+    pub fn from_pgn(pgn: &str) -> Result<Self, PgnParseError> {
+        let (tag_pairs, movetext) = PgnTags::parse_tag_section(pgn).map_err(PgnParseError::TagPair)?;
+        let mut board = match tag_pairs.get("FEN") {
+            Some(fen) => Board::from_fen(Fen::try_from(fen.as_str()).map_err(PgnParseError::Fen)?),
+            _ => Board::default(),
+        };
+        for san in helpers::movetext_sans(movetext) {
+            board.make_move_san(&san).map_err(|_| PgnParseError::IllegalMove(san))?;
+        }
+        let results = ["1-0", "0-1", "1/2-1/2", "*"];
+        let result = match movetext.split_ascii_whitespace().next_back().filter(|token| results.contains(token)) {
+            Some(token) => Some(token),
+            _ => tag_pairs.get("Result").map(|r| r.as_str()),
+        };
+        match (result, board.game_result()) {
+            (None | Some("*"), _) => (),
+            (Some("1-0"), None) => board.resign(Color::Black).unwrap(),
+            (Some("0-1"), None) => board.resign(Color::White).unwrap(),
+            (Some("1/2-1/2"), None) => board.agree_draw().unwrap(),
+            (Some(res), Some(game_result)) if res == game_result.to_string() => (),
+            (Some(res), Some(game_result)) => return Err(PgnParseError::Result(format!("the game ended {game_result} on the board but the result is {res}"))),
+            (Some(res), None) => return Err(PgnParseError::Result(format!("'{res}' is not a valid result"))),
+        }
+        Ok(board)
+    }
+
+    /// Renders every position of the game, from the initial position to the current one, as a PNG image from the perspective
+    /// of the side `perspective`, with squares `square_px` pixels wide and the default [image properties](img::PositionImageProperties).
+    /// The frames can be assembled into an animation (e.g. a GIF) with an encoder of choice.
+    #[cfg(feature = "img")]
+    pub fn render_frames(&self, perspective: Color, square_px: u32) -> Result<Vec<Vec<u8>>, InvalidPositionImagePropertiesError> {
+        self.position_history
+            .iter()
+            .chain([&self.position])
+            .map(|position| {
+                let props = img::PositionImageProperties {
+                    size: square_px as usize * 8,
+                    ..Default::default()
+                };
+                let mut png = Vec::new();
+                image::DynamicImage::ImageRgba8(img::position_to_image(position, props, perspective)?)
+                    .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+                    .unwrap();
+                Ok(png)
+            })
+            .collect()
+    }
+
+    /// Returns the castling rights before each ply of the game, followed by the current castling rights.
+    /// The element at index `i` holds the rights before ply `i` was played, so comparing consecutive elements
+    /// reveals the ply on which a castling right was lost.
+    pub fn castling_rights_history(&self) -> Vec<CastlingRights> {
+        self.position_history.iter().chain([&self.position]).map(|p| p.castling_rights()).collect()
+    }
+
+    /// Returns every position of the game, including the current one, paired with the winner of the game
+    /// (`None` if the game was drawn or is still ongoing). This is the standard target for supervised learning.
+    pub fn training_samples(&self) -> Vec<(Position, Option<Color>)> {
+        let winner = match self.game_result() {
+            Some(GameResult::Wins(c, _)) => Some(c),
+            _ => None,
+        };
+        self.position_history.iter().chain([&self.position]).map(|p| (p.clone(), winner)).collect()
+    }
+
     /// Returns the current `Position` on the board.
     pub fn position(&self) -> &Position {
         &self.position
     }
+
+    /// Returns the moves played on the board, in the order they were played.
+    pub fn move_history(&self) -> &[Move] {
+        &self.move_history
+    }
+
+    /// Returns the positions before each move played on the board, in play order, so that the move at index `i` of the
+    /// [move history](Board::move_history) was played in the position at index `i`. The current position is not included.
+    pub fn position_history(&self) -> &[Position] {
+        &self.position_history
+    }
+
+    /// Returns the number of plies (halfmoves) played on the board.
+    pub fn ply_count(&self) -> usize {
+        self.move_history.len()
+    }
 }
 
 impl Default for Board {
@@ -391,3 +1180,90 @@ impl fmt::Display for Board {
         self.position.fmt(f)
     }
 }
+
+/// The serialized form of a `Board`: the initial FEN, the moves played in UCI (see [`Board::moves_uci`]) and the state that
+/// cannot be derived from them. A board is deserialized by replaying the moves, so inconsistent data is rejected.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Board")]
+struct SerializedBoard {
+    initial_fen: Fen,
+    moves: Vec<String>,
+    resigned_side: Option<Color>,
+    flagged_side: Option<Color>,
+    draw_reason: Option<DrawType>,
+    draw_offer: Option<Color>,
+    time_control: Option<TimeControl>,
+    /// The time remaining on each side's clock before each move and after the last one (empty if the game is not timed)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    clock_history: Vec<[Duration; 2]>,
+}
+
+#[cfg(feature = "serde")]
+impl SerializedBoard {
+    /// Reconstructs the board by replaying the moves, returning an error if the moves or game state are inconsistent.
+    fn into_board(self) -> Result<Board, String> {
+        let mut board = Board::from_fen(self.initial_fen);
+        for uci in &self.moves {
+            board.make_move_uci(uci).map_err(|e| e.to_string())?;
+        }
+        let ending = if let Some(side) = self.resigned_side {
+            board.resign(side)
+        } else if let Some(side) = self.flagged_side {
+            board.flag(side)
+        } else {
+            match self.draw_reason {
+                Some(DrawType::Agreement) => board.agree_draw(),
+                Some(DrawType::ThreefoldRepetition | DrawType::FiftyMoveRule) => board.claim_draw(),
+                _ => Ok(()),
+            }
+        };
+        ending.map_err(|e| e.to_string())?;
+        if (board.resigned_side, board.flagged_side, board.draw_reason) != (self.resigned_side, self.flagged_side, self.draw_reason) {
+            return Err("the game status does not match the moves played".to_owned());
+        }
+        board.draw_offer = self.draw_offer;
+        match (self.time_control, self.clock_history.split_last()) {
+            (None, None) => (),
+            (Some(time_control), Some((&current, history))) if history.len() == self.moves.len() => {
+                board.time_control = Some(time_control);
+                board.time_remaining = current;
+                board.time_remaining_history = history.to_vec();
+            }
+            _ => return Err("expected a clock reading before each move and after the last one in a timed game, and none otherwise".to_owned()),
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    /// Serializes the board as its initial FEN, the moves played in UCI and the game state that cannot be derived from them
+    /// (resignation, loss on time, agreed or claimed draws, a pending draw offer and the clocks).
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let clock_history = match self.time_control {
+            Some(_) => self.time_remaining_history.iter().copied().chain([self.time_remaining]).collect(),
+            None => Vec::new(),
+        };
+        let serialized = SerializedBoard {
+            initial_fen: self.initial_fen.clone(),
+            moves: self.moves_uci(),
+            resigned_side: self.resigned_side,
+            flagged_side: self.flagged_side,
+            draw_reason: self.draw_reason,
+            draw_offer: self.draw_offer,
+            time_control: self.time_control,
+            clock_history,
+        };
+        serde::Serialize::serialize(&serialized, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    /// Deserializes a board by replaying its moves from the initial FEN, returning an error if any move is illegal or the
+    /// game state does not match the moves.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <SerializedBoard as serde::Deserialize>::deserialize(deserializer)?.into_board().map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,149 @@
+use super::{Fen, InvalidEpdError, InvalidSanMoveError, Move};
+use std::{collections::HashMap, fmt};
+
+/// Represents EPD (Extended Position Description): the first four fields of an FEN followed by a list of operations.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Epd {
+    fen: Fen,
+    operations: HashMap<String, String>,
+    /// The moves of the `bm` operation, resolved in the position when the EPD was parsed
+    best_moves: Vec<Move>,
+    /// The moves of the `am` operation, resolved in the position when the EPD was parsed
+    avoid_moves: Vec<Move>,
+}
+
+impl Epd {
+    /// Returns the game state described by the EPD. The halfmove clock and fullmove number are taken from the `hmvc` and
+    /// `fmvn` operations if present, and default to 0 and 1 respectively otherwise.
+    pub fn fen(&self) -> &Fen {
+        &self.fen
+    }
+
+    /// Returns the operations of the EPD, mapping each opcode to its operand (with any surrounding quotes removed).
+    pub fn operations(&self) -> &HashMap<String, String> {
+        &self.operations
+    }
+
+    /// Returns the operand of the operation with the given opcode (`None` if the EPD has no such operation).
+    pub fn operation(&self, opcode: &str) -> Option<&str> {
+        self.operations.get(opcode).map(|s| s.as_str())
+    }
+
+    /// Returns the best moves given by the `bm` operation, in the order they are listed (empty if the EPD has no such operation).
+    pub fn best_moves(&self) -> &[Move] {
+        &self.best_moves
+    }
+
+    /// Returns the moves to avoid given by the `am` operation, in the order they are listed (empty if the EPD has no such operation).
+    pub fn avoid_moves(&self) -> &[Move] {
+        &self.avoid_moves
+    }
+
+    /// Returns the identifier of the position given by the `id` operation (`None` if the EPD has no such operation).
+    pub fn id(&self) -> Option<&str> {
+        self.operation("id")
+    }
+
+    /// Interprets a space-separated list of SAN moves in the position of the given FEN, returning an error if any move is invalid or illegal.
+    fn resolve_san_moves(fen: &Fen, operand: &str) -> Result<Vec<Move>, InvalidSanMoveError> {
+        operand.split_ascii_whitespace().map(|san| fen.position().san_to_move(san)).collect()
+    }
+
+    /// Parses an EPD, resolving the moves of the `bm` and `am` operations only if `resolve_moves` is set
+    /// (otherwise `best_moves` and `avoid_moves` are left empty).
+    pub(crate) fn parse(epd: &str, resolve_moves: bool) -> Result<Self, InvalidEpdError> {
+        let epd = epd.trim();
+        let mut fields = Vec::new();
+        let mut rest = epd;
+        for _ in 0..4 {
+            let (field, remaining) = rest.split_once(' ').unwrap_or((rest, ""));
+            if field.is_empty() {
+                return Err(InvalidEpdError::FourFields);
+            }
+            fields.push(field);
+            rest = remaining.trim_start();
+        }
+        let mut operations = HashMap::new();
+        for op in Self::split_operations(rest)? {
+            let (opcode, operand) = op.split_once(' ').unwrap_or((&op, ""));
+            if !(opcode.starts_with(|c: char| c.is_ascii_alphabetic()) && opcode.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')) {
+                return Err(InvalidEpdError::Operation(op.clone()));
+            }
+            let operand = operand.trim();
+            let operand = operand.strip_prefix('"').and_then(|o| o.strip_suffix('"')).unwrap_or(operand);
+            operations.insert(opcode.to_owned(), operand.to_owned());
+        }
+        let clock = |opcode, default: &str| match operations.get(opcode) {
+            Some(operand) if operand.parse::<usize>().is_err() => Err(InvalidEpdError::Operation(format!("{opcode} {operand}"))),
+            Some(operand) => Ok(operand.clone()),
+            None => Ok(default.to_owned()),
+        };
+        let (halfmove_clock, fullmove_number) = (clock("hmvc", "0")?, clock("fmvn", "1")?);
+        let fen = Fen::try_from([fields.join(" "), halfmove_clock, fullmove_number].join(" ").as_str()).map_err(InvalidEpdError::BoardFields)?;
+        let san_moves = |opcode| match operations.get(opcode) {
+            Some(operand) if resolve_moves => Self::resolve_san_moves(&fen, operand).map_err(|_| InvalidEpdError::Operation(format!("{opcode} {operand}"))),
+            _ => Ok(Vec::new()),
+        };
+        let (best_moves, avoid_moves) = (san_moves("bm")?, san_moves("am")?);
+        Ok(Self {
+            fen,
+            operations,
+            best_moves,
+            avoid_moves,
+        })
+    }
+
+    /// Splits the operations section of an EPD into individual operations, ignoring semicolons within quoted strings.
+    fn split_operations(text: &str) -> Result<Vec<String>, InvalidEpdError> {
+        let mut operations = Vec::new();
+        let (mut current, mut quoted) = (String::new(), false);
+        for c in text.chars() {
+            match c {
+                '"' => quoted = !quoted,
+                ';' if !quoted => {
+                    operations.push(current.trim().to_owned());
+                    current.clear();
+                    continue;
+                }
+                _ => (),
+            }
+            current.push(c);
+        }
+        if quoted {
+            return Err(InvalidEpdError::Operation(current.trim().to_owned()));
+        }
+        if !current.trim().is_empty() {
+            operations.push(current.trim().to_owned());
+        }
+        Ok(operations.into_iter().filter(|op| !op.is_empty()).collect())
+    }
+}
+
+impl TryFrom<&str> for Epd {
+    type Error = InvalidEpdError;
+
+    /// Attempts to construct an `Epd` object from a string slice, returning an error if the board fields or any operation are invalid,
+    /// including a `bm` or `am` operation with a move that is not legal SAN in the position.
+    fn try_from(epd: &str) -> Result<Self, Self::Error> {
+        Self::parse(epd, true)
+    }
+}
+
+impl fmt::Display for Epd {
+    /// Returns an EPD string representing this object, with the operations sorted by opcode.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut operations: Vec<_> = self.operations.iter().collect();
+        operations.sort();
+        write!(f, "{}", self.fen.to_epd())?;
+        for (opcode, operand) in operations {
+            if operand.is_empty() {
+                write!(f, " {opcode};")?;
+            } else if operand.contains(|c: char| c.is_whitespace() || c == ';') {
+                write!(f, " {opcode} \"{operand}\";")?;
+            } else {
+                write!(f, " {opcode} {operand};")?;
+            }
+        }
+        Ok(())
+    }
+}
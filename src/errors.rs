@@ -1,25 +1,47 @@
 //! Contains all rschess error types.
 
-use super::Move;
+use super::{Move, PositionProblem};
 use thiserror::Error;
 
-/// Conveys that the given FEN is invalid.
+/// Conveys that the given FEN is invalid, with each variant naming the field that failed and carrying its offending text.
 #[derive(Error, Debug)]
 pub enum InvalidFenError {
     #[error("Invalid FEN: expected six fields separated by a space")]
     SixFields,
-    #[error("Invalid FEN board data: {0}")]
-    BoardData(String),
-    #[error("Invalid FEN: active color must be 'w' or 'b'")]
-    ActiveColor,
-    #[error("Invalid FEN castling rights: {0}")]
-    CastlingRights(String),
-    #[error("Invalid FEN en passant target square: this field must be '-' or a valid square name on the 3rd or 6th rank")]
-    EnPassantTargetSquare,
-    #[error("Invalid FEN halfmove clock: halfmove clock must be in the range 0..=150")]
-    HalfmoveClock,
-    #[error("Invalid FEN fullmove number: fullmove number must be in the range 1..")]
-    FullmoveNumber,
+    /// `field` is the offending piece placement field, and `reason` describes what is wrong with it.
+    #[error("Invalid FEN board data '{field}': {reason}")]
+    BoardData { field: String, reason: String },
+    /// The tuple value is the offending active color field.
+    #[error("Invalid FEN: active color must be 'w' or 'b', got '{0}'")]
+    ActiveColor(String),
+    /// `field` is the offending castling rights field, and `reason` describes what is wrong with it.
+    #[error("Invalid FEN castling rights '{field}': {reason}")]
+    CastlingRights { field: String, reason: String },
+    /// The tuple value is the offending en passant target square field.
+    #[error("Invalid FEN en passant target square: got '{0}', this field must be '-' or a valid square name on the 6th rank with white to move or the 3rd rank with black to move, which an enemy pawn could have just passed with a double push")]
+    EnPassantTargetSquare(String),
+    /// The tuple value is the offending halfmove clock field.
+    #[error("Invalid FEN halfmove clock: got '{0}', halfmove clock must be in the range 0..=150")]
+    HalfmoveClock(String),
+    /// The tuple value is the offending fullmove number field.
+    #[error("Invalid FEN fullmove number: got '{0}', fullmove number must be in the range 1..")]
+    FullmoveNumber(String),
+}
+
+/// The error returned when parsing an FEN string with [`Fen::try_from`](super::Fen), an alias of [`InvalidFenError`]
+/// under which each failing field (piece placement, active color, castling rights, en passant target square, halfmove clock
+/// or fullmove number) is reported as a separate variant.
+pub type FenParseError = InvalidFenError;
+
+/// Conveys that the given EPD is invalid.
+#[derive(Error, Debug)]
+pub enum InvalidEpdError {
+    #[error("Invalid EPD: expected at least four fields separated by a space")]
+    FourFields,
+    #[error("Invalid EPD board fields: {0}")]
+    BoardFields(InvalidFenError),
+    #[error("Invalid EPD operation: '{0}'")]
+    Operation(String),
 }
 
 /// Conveys that the given piece character is invalid.
@@ -67,6 +89,11 @@ pub enum InvalidUciMoveError {
 #[error("Invalid SAN move: '{0}' is either invalid or illegal in this position")]
 pub struct InvalidSanMoveError(pub String);
 
+/// Conveys that the given LAN move is either invalid or illegal.
+#[derive(Error, Debug)]
+#[error("Invalid LAN move: '{0}' is either invalid or illegal in this position")]
+pub struct InvalidLanMoveError(pub String);
+
 /// Conveys that the given square name is invalid.
 #[derive(Error, Debug)]
 #[error("Invalid square name: {0}{1}")]
@@ -77,13 +104,54 @@ pub struct InvalidSquareNameError(pub char, pub char);
 #[error("Invalid square index: {0}, a square index must be in the range 0..=63")]
 pub struct InvalidSquareIndexError(pub usize);
 
-/// Conveys that this action cannot be taken after the game is over.
+/// Conveys that the given position is illegal, with the tuple value being every problem found by [`Position::validate`](super::Position::validate).
+#[derive(Error, Debug)]
+#[error("Invalid position: {}", .0.iter().map(|p| p.to_string()).collect::<Vec<_>>().join("; "))]
+pub struct InvalidPositionError(pub Vec<PositionProblem>);
+
+/// Conveys that this action cannot be taken after the game is over (or, for a draw claim, that the game cannot be ended this way).
 #[derive(Error, Debug)]
 pub enum GameOverError {
     #[error("Game over: a player cannot resign when the game is over")]
     Resignation,
     #[error("Game over: players cannot agree to a draw when the game is over")]
     AgreementDraw,
+    #[error("Game over: a player cannot lose on time when the game is over")]
+    Timeout,
+    #[error("Game over: a draw cannot be claimed when the game is over")]
+    DrawClaim,
+    #[error("Game over: a draw cannot be offered when the game is over")]
+    DrawOffer,
+    /// Conveys that a draw was claimed while the game is ongoing, but neither threefold repetition nor the fifty-move rule applies.
+    #[error("Invalid draw claim: the position has not occurred three times and the fifty-move rule does not apply")]
+    NoDrawToClaim,
+    /// Conveys that a draw offer was accepted while the game is ongoing, but no draw has been offered.
+    #[error("Invalid draw acceptance: no draw has been offered")]
+    NoDrawOffer,
+}
+
+/// Conveys that the given PGN could not be parsed into a [`Board`](super::Board).
+#[derive(Error, Debug)]
+pub enum PgnParseError {
+    #[error("Invalid PGN tag pair: '{0}'")]
+    TagPair(String),
+    #[error("Invalid PGN FEN tag: {0}")]
+    Fen(InvalidFenError),
+    #[error("Invalid PGN movetext: '{0}' is either invalid or illegal in this position")]
+    IllegalMove(String),
+    #[error("Invalid PGN result: {0}")]
+    Result(String),
+}
+
+/// Conveys that the given UCI `position` command could not be parsed into a [`Board`](super::Board).
+#[derive(Error, Debug)]
+pub enum UciPositionError {
+    #[error("Invalid UCI position command: expected 'position startpos' or 'position fen <FEN>', optionally followed by 'moves' and a list of moves, got '{0}'")]
+    Command(String),
+    #[error("Invalid UCI position FEN: {0}")]
+    Fen(InvalidFenError),
+    #[error("Invalid UCI position moves: '{0}' is either invalid or illegal in this position")]
+    IllegalMove(String),
 }
 
 /// Conveys that the given PGN text is invalid.
@@ -1,20 +1,102 @@
-use super::{helpers, Color, InvalidFenError, Piece, PieceType, Position};
-use std::fmt;
+use super::{helpers, Color, Epd, FenParseError, InvalidEpdError, InvalidFenError, Piece, PieceType, Position, PositionProblem};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
 
 /// Represents FEN (Forsyth-Edwards Notation).
-#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+///
+/// Two `Fen` objects are equal if they describe the same position and counters, regardless of the castling rights notation used.
+#[derive(Clone, Debug)]
 pub struct Fen {
     pub(crate) position: Position,
     pub(crate) halfmove_clock: usize,
     pub(crate) fullmove_number: usize,
+    /// Whether the castling rights were parsed from Shredder-FEN file letters, in which case they are displayed the same way
+    pub(crate) shredder: bool,
+}
+
+impl PartialEq for Fen {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position && self.halfmove_clock == other.halfmove_clock && self.fullmove_number == other.fullmove_number
+    }
+}
+
+impl Eq for Fen {}
+
+impl Hash for Fen {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.position.hash(state);
+        self.halfmove_clock.hash(state);
+        self.fullmove_number.hash(state);
+    }
 }
 
 impl Fen {
+    /// Constructs a `Fen` object from a position and move counters, returning an error if the position could not
+    /// have been parsed from a FEN string or if the counters are out of range.
+    pub fn new(position: Position, halfmove_clock: usize, fullmove_number: usize) -> Result<Self, InvalidFenError> {
+        // Shredder-FEN castling letters are used so that the FEN fields can be written even if a king is missing
+        let fen_field = |i| position.to_fen_with(true).split(' ').nth(i).unwrap().to_owned();
+        for problem in position.validate() {
+            match problem {
+                PositionProblem::InvalidEnPassantTarget(sq) => {
+                    let (file, rank) = helpers::idx_to_sq(sq);
+                    return Err(InvalidFenError::EnPassantTargetSquare(format!("{file}{rank}")));
+                }
+                PositionProblem::InvalidCastlingRight(_) => {
+                    return Err(InvalidFenError::CastlingRights {
+                        field: fen_field(2),
+                        reason: problem.to_string(),
+                    })
+                }
+                PositionProblem::MissingKing(_) | PositionProblem::TooManyKings(_) | PositionProblem::PawnOnBackRank(_) | PositionProblem::OpponentInCheck => {
+                    return Err(InvalidFenError::BoardData {
+                        field: fen_field(0),
+                        reason: problem.to_string(),
+                    })
+                }
+                _ => (),
+            }
+        }
+        if halfmove_clock > 150 {
+            return Err(InvalidFenError::HalfmoveClock(halfmove_clock.to_string()));
+        }
+        if fullmove_number < 1 {
+            return Err(InvalidFenError::FullmoveNumber(fullmove_number.to_string()));
+        }
+        Ok(Self {
+            position,
+            halfmove_clock,
+            fullmove_number,
+            shredder: false,
+        })
+    }
+
+    /// Constructs a `Fen` object from an EPD or a FEN without move counters (e.g. "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -"),
+    /// returning an error if it is invalid. The halfmove clock and fullmove number are taken from the `hmvc` and `fmvn` operations
+    /// if present, and default to 0 and 1 respectively otherwise; any other operations are ignored, so the moves of `bm` and `am`
+    /// operations are not checked.
+    pub fn from_epd(epd: &str) -> Result<Self, InvalidEpdError> {
+        Ok(Epd::parse(epd, false)?.fen().clone())
+    }
+
+    /// Returns the first four fields of the FEN (board data, active color, castling rights and en passant target square),
+    /// i.e. an EPD without operations.
+    pub fn to_epd(&self) -> String {
+        self.position.to_fen_with(self.shredder)
+    }
+
     /// Returns the position represented by the `Fen` object.
     pub fn position(&self) -> &Position {
         &self.position
     }
 
+    /// Returns the name of the en passant target square as a (file, rank) pair, if there is one.
+    pub fn en_passant_square(&self) -> Option<(char, char)> {
+        self.position.ep_target.map(helpers::idx_to_sq)
+    }
+
     /// Returns the halfmove clock.
     pub fn halfmove_clock(&self) -> usize {
         self.halfmove_clock
@@ -24,13 +106,31 @@ impl Fen {
     pub fn fullmove_number(&self) -> usize {
         self.fullmove_number
     }
+
+    /// Returns the files of the rooks that the castling rights refer to, in the order [K, Q, k, q].
+    pub fn castling_rook_files(&self) -> [Option<char>; 4] {
+        self.position.castling_rights.map(|right| right.map(|sq| helpers::idx_to_sq(sq).0))
+    }
+
+    /// Checks whether two `Fen` objects describe the same game state.
+    ///
+    /// The `PartialEq` implementation compares the positions and counters of `Fen` objects structurally (ignoring only the castling
+    /// rights notation), so two FENs that differ only in an en passant target square are considered unequal even if no en passant
+    /// capture is possible. This function
+    /// instead ignores the en passant target square when no legal en passant capture exists, which is useful when
+    /// comparing FENs from sources that handle the en passant field differently (e.g. engines and databases).
+    pub fn semantically_eq(&self, other: &Fen) -> bool {
+        self.position.with_normalized_ep() == other.position.with_normalized_ep() && self.halfmove_clock == other.halfmove_clock && self.fullmove_number == other.fullmove_number
+    }
 }
 
 impl TryFrom<&str> for Fen {
-    type Error = InvalidFenError;
+    type Error = FenParseError;
 
-    /// Attempts to construct a `Fen` object from a string slice, returning an error if it is invalid.
-    /// **Shredder-FEN is NOT supported**.
+    /// Attempts to construct a `Fen` object from a string slice, returning an error if it is invalid, including an en passant
+    /// target square that could not have been created by a pawn double push (see [`Position::validate_en_passant`]).
+    /// Chess960 positions are supported: 'K', 'Q', 'k' and 'q' refer to the only rook on the respective side of the king,
+    /// and Shredder-FEN file letters (such as 'H' or 'b') may be used to identify a castling rook when there is more than one.
     fn try_from(fen: &str) -> Result<Self, Self::Error> {
         let mut content = [None; 64];
         let fields: Vec<_> = fen.trim().split(' ').collect();
@@ -38,10 +138,12 @@ impl TryFrom<&str> for Fen {
         if nfields != 6 {
             return Err(InvalidFenError::SixFields);
         }
+        let board_data_err = |reason: String| InvalidFenError::BoardData { field: fields[0].to_owned(), reason };
+        let castling_err = |reason: String| InvalidFenError::CastlingRights { field: fields[2].to_owned(), reason };
         let ranks: Vec<_> = fields[0].split('/').collect();
         let nranks = ranks.len();
         if nranks != 8 {
-            return Err(InvalidFenError::BoardData(format!("expected eight ranks of pieces separated by forward-slashes, got {nranks}")));
+            return Err(board_data_err(format!("expected eight ranks of pieces separated by forward-slashes, got {nranks}")));
         }
         let mut wk_seen = false;
         let mut wk_pos = 0;
@@ -53,17 +155,15 @@ impl TryFrom<&str> for Fen {
             let mut rank_filled = 0;
             for piece_char in rank.chars().rev() {
                 if rank_filled == 8 {
-                    return Err(InvalidFenError::BoardData(format!("rank {rankn} cannot have pieces beyond the h file (8 squares already occupied)")));
+                    return Err(board_data_err(format!("rank {rankn} cannot have pieces beyond the h file (8 squares already occupied)")));
                 }
                 if piece_char.is_ascii_digit() {
                     let empty_space = piece_char.to_digit(10).unwrap() as usize;
                     if !(1..=8).contains(&empty_space) {
-                        return Err(InvalidFenError::BoardData(format!(
-                            "{empty_space} is not a valid character for board data, digits must be in the range 1..=8"
-                        )));
+                        return Err(board_data_err(format!("{empty_space} is not a valid character for board data, digits must be in the range 1..=8")));
                     }
                     if empty_space > 8 - rank_filled {
-                        return Err(InvalidFenError::BoardData(format!(
+                        return Err(board_data_err(format!(
                             "rank {rankn} only has 8 squares, {rank_filled} of which is/are occupied. {empty_space} more squares of empty space cannot be accomodated"
                         )));
                     }
@@ -75,110 +175,121 @@ impl TryFrom<&str> for Fen {
                             match piece {
                                 Piece(PieceType::K, Color::White) => {
                                     if wk_seen {
-                                        return Err(InvalidFenError::BoardData("white cannot have more than one king".to_owned()));
+                                        return Err(board_data_err("white cannot have more than one king".to_owned()));
                                     }
                                     wk_seen = true;
                                     wk_pos = ptr;
                                 }
                                 Piece(PieceType::K, Color::Black) => {
                                     if bk_seen {
-                                        return Err(InvalidFenError::BoardData("black cannot have more than one king".to_owned()));
+                                        return Err(board_data_err("black cannot have more than one king".to_owned()));
                                     }
                                     bk_seen = true;
                                     bk_pos = ptr;
                                 }
-                                Piece(PieceType::P, _) => {
-                                    if !(8..56).contains(&ptr) {
-                                        return Err(InvalidFenError::BoardData("there cannot be pawns on the 1st and 8th ranks".to_owned()));
-                                    }
+                                Piece(PieceType::P, _) if !(8..56).contains(&ptr) => {
+                                    return Err(board_data_err("there cannot be pawns on the 1st and 8th ranks".to_owned()));
                                 }
                                 _ => (),
                             }
                             Some(piece)
                         }
-                        Err(e) => return Err(InvalidFenError::BoardData(e.to_string())),
+                        Err(e) => return Err(board_data_err(e.to_string())),
                     };
                     rank_filled += 1;
                     ptr = ptr.saturating_sub(1);
                 }
             }
             if rank_filled != 8 {
-                return Err(InvalidFenError::BoardData(format!("rank {rankn} does not have data occupying all 8 squares")));
+                return Err(board_data_err(format!("rank {rankn} does not have data occupying all 8 squares")));
             }
             rankn -= 1;
         }
         if !(wk_seen && bk_seen) {
-            return Err(InvalidFenError::BoardData("a valid chess position must have one white king and one black king".to_owned()));
+            return Err(board_data_err("a valid chess position must have one white king and one black king".to_owned()));
         }
         let turn = fields[1];
         let side = match Color::try_from(turn) {
             Ok(c) => c,
-            _ => return Err(InvalidFenError::ActiveColor),
+            _ => return Err(InvalidFenError::ActiveColor(turn.to_owned())),
         };
         if helpers::king_capture_pseudolegal(&content, side) {
-            return Err(InvalidFenError::BoardData("when one side is in check, it cannot be the other side's turn to move".to_owned()));
+            return Err(board_data_err("when one side is in check, it cannot be the other side's turn to move".to_owned()));
         }
         let castling = fields[2];
         let len_castling = castling.len();
         if !((1..=4).contains(&len_castling)) {
-            return Err(InvalidFenError::CastlingRights("expected castling rights to be 1 to 4 characters long".to_owned()));
+            return Err(castling_err("expected castling rights to be 1 to 4 characters long".to_owned()));
         }
         let mut castling_rights_old = [false; 4];
+        let mut shredder_rights = [None; 4];
         if castling != "-" {
             for ch in castling.chars() {
                 match ch {
                     'K' => {
                         if wk_pos > 6 {
-                            return Err(InvalidFenError::CastlingRights("white king must be from a1 to g1 to have kingside castling rights".to_owned()));
+                            return Err(castling_err("white king must be from a1 to g1 to have kingside castling rights".to_owned()));
                         }
                         if castling_rights_old[0] {
-                            return Err(InvalidFenError::CastlingRights("found more than one occurrence of 'K'".to_owned()));
+                            return Err(castling_err("found more than one occurrence of 'K'".to_owned()));
                         }
                         castling_rights_old[0] = true;
                     }
                     'Q' => {
                         if !(1..=7).contains(&wk_pos) {
-                            return Err(InvalidFenError::CastlingRights("white king must be from b1 to h1 to have queenside castling rights".to_owned()));
+                            return Err(castling_err("white king must be from b1 to h1 to have queenside castling rights".to_owned()));
                         }
                         if castling_rights_old[1] {
-                            return Err(InvalidFenError::CastlingRights("found more than one occurrence of 'Q'".to_owned()));
+                            return Err(castling_err("found more than one occurrence of 'Q'".to_owned()));
                         }
                         castling_rights_old[1] = true;
                     }
                     'k' => {
                         if !(56..=62).contains(&bk_pos) {
-                            return Err(InvalidFenError::CastlingRights("black king must be from a8 to g8 to have kingside castling rights".to_owned()));
+                            return Err(castling_err("black king must be from a8 to g8 to have kingside castling rights".to_owned()));
                         }
                         if castling_rights_old[2] {
-                            return Err(InvalidFenError::CastlingRights("found more than one occurrence of 'k'".to_owned()));
+                            return Err(castling_err("found more than one occurrence of 'k'".to_owned()));
                         }
                         castling_rights_old[2] = true;
                     }
                     'q' => {
                         if !(57..=63).contains(&bk_pos) {
-                            return Err(InvalidFenError::CastlingRights("black king must be from b8 to h8 to have queenside castling rights".to_owned()));
+                            return Err(castling_err("black king must be from b8 to h8 to have queenside castling rights".to_owned()));
                         }
                         if castling_rights_old[3] {
-                            return Err(InvalidFenError::CastlingRights("found more than one occurrence of 'q'".to_owned()));
+                            return Err(castling_err("found more than one occurrence of 'q'".to_owned()));
                         }
                         castling_rights_old[3] = true;
                     }
-                    _ => return Err(InvalidFenError::CastlingRights("expected '-' or a subset of 'KQkq'".to_owned())),
+                    'A'..='H' | 'a'..='h' => {
+                        let (color, king_pos, back_rank) = if ch.is_ascii_uppercase() { (Color::White, wk_pos, 0) } else { (Color::Black, bk_pos, 56) };
+                        let rook_pos = back_rank + (ch.to_ascii_lowercase() as usize - 97);
+                        if content[rook_pos] != Some(Piece(PieceType::R, color)) || !(back_rank..back_rank + 8).contains(&king_pos) {
+                            return Err(castling_err(format!("'{ch}' must refer to a rook on the same rank as its king")));
+                        }
+                        let i = if color.is_white() { 0 } else { 2 } + if rook_pos > king_pos { 0 } else { 1 };
+                        if shredder_rights[i].is_some() {
+                            return Err(castling_err(format!("found more than one castling right on the same side as '{ch}'")));
+                        }
+                        shredder_rights[i] = Some(rook_pos);
+                    }
+                    _ => return Err(castling_err("expected '-', a subset of 'KQkq', or Shredder-FEN file letters".to_owned())),
                 }
             }
         }
         let count_rooks = |rng, color| helpers::count_piece(rng, Piece(PieceType::R, color), &content);
         if castling_rights_old[0] && count_rooks(wk_pos + 1..8, Color::White) != 1 {
-            return Err(InvalidFenError::CastlingRights("white must have exactly one king's rook to have kingside castling rights".to_owned()));
+            return Err(castling_err("white must have exactly one king's rook to have kingside castling rights".to_owned()));
         }
         if castling_rights_old[1] && count_rooks(0..wk_pos, Color::White) != 1 {
-            return Err(InvalidFenError::CastlingRights("white must have exactly one queen's rook to have queenside castling rights".to_owned()));
+            return Err(castling_err("white must have exactly one queen's rook to have queenside castling rights".to_owned()));
         }
         if castling_rights_old[2] && count_rooks(bk_pos + 1..64, Color::Black) != 1 {
-            return Err(InvalidFenError::CastlingRights("black must have exactly one king's rook to have kingside castling rights".to_owned()));
+            return Err(castling_err("black must have exactly one king's rook to have kingside castling rights".to_owned()));
         }
         if castling_rights_old[3] && count_rooks(56..bk_pos, Color::Black) != 1 {
-            return Err(InvalidFenError::CastlingRights("black must have exactly one queen's rook to have queenside castling rights".to_owned()));
+            return Err(castling_err("black must have exactly one queen's rook to have queenside castling rights".to_owned()));
         }
         let find_rook = |rng, color| helpers::find_pieces(Piece(PieceType::R, color), rng, &content)[0];
         let mut castling_rights = [None; 4];
@@ -194,20 +305,28 @@ impl TryFrom<&str> for Fen {
         if castling_rights_old[3] {
             castling_rights[3] = Some(find_rook(56..bk_pos, Color::Black));
         }
+        for (i, right) in shredder_rights.into_iter().enumerate() {
+            if right.is_some() {
+                if castling_rights[i].is_some() {
+                    return Err(castling_err("found both a castling right and a file letter for the same side".to_owned()));
+                }
+                castling_rights[i] = right;
+            }
+        }
         let ep = fields[3];
         let len_ep = ep.len();
         if !((1..=2).contains(&len_ep)) {
-            return Err(InvalidFenError::EnPassantTargetSquare);
+            return Err(InvalidFenError::EnPassantTargetSquare(ep.to_owned()));
         }
         let mut ep_target = None;
         if ep != "-" {
-            let err = Err(InvalidFenError::EnPassantTargetSquare);
+            let err = Err(InvalidFenError::EnPassantTargetSquare(ep.to_owned()));
             if len_ep != 2 {
                 return err;
             }
             let file = ep.chars().next().unwrap();
             let rank = ep.chars().nth(1).unwrap();
-            if !(('a'..='h').contains(&file) && ['3', '6'].contains(&rank)) {
+            if !(('a'..='h').contains(&file) && rank == if side.is_white() { '6' } else { '3' }) {
                 return err;
             }
             ep_target = Some(helpers::sq_to_idx(file, rank));
@@ -218,28 +337,54 @@ impl TryFrom<&str> for Fen {
             castling_rights,
             ep_target,
         };
+        if !position.validate_en_passant() {
+            return Err(InvalidFenError::EnPassantTargetSquare(ep.to_owned()));
+        }
         let halfmoves = fields[4];
-        let halfmove_clock: usize = halfmoves.parse().map_err(|_| InvalidFenError::HalfmoveClock)?;
+        let halfmove_clock: usize = halfmoves.parse().map_err(|_| InvalidFenError::HalfmoveClock(halfmoves.to_owned()))?;
         if halfmove_clock > 150 {
-            return Err(InvalidFenError::HalfmoveClock);
+            return Err(InvalidFenError::HalfmoveClock(halfmoves.to_owned()));
         }
         let fullmoves = fields[5];
-        let fullmove_number: usize = fullmoves.parse().map_err(|_| InvalidFenError::FullmoveNumber)?;
+        let fullmove_number: usize = fullmoves.parse().map_err(|_| InvalidFenError::FullmoveNumber(fullmoves.to_owned()))?;
         if fullmove_number < 1 {
-            return Err(InvalidFenError::FullmoveNumber);
+            return Err(InvalidFenError::FullmoveNumber(fullmoves.to_owned()));
         }
         Ok(Self {
             position,
             halfmove_clock,
             fullmove_number,
+            shredder: castling != "-" && !castling.contains(['K', 'Q', 'k', 'q']),
         })
     }
 }
 
 impl fmt::Display for Fen {
     /// Returns an FEN string representing this object.
-    /// If standard FEN is inadequate for representing castling rights, a mixture of standard FEN and Shredder-FEN will be generated.
+    /// If standard FEN is inadequate for representing castling rights, a mixture of standard FEN and Shredder-FEN will be generated (X-FEN),
+    /// unless the object was parsed from Shredder-FEN castling rights, in which case Shredder-FEN is generated.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", [self.position.to_fen(), self.halfmove_clock.to_string(), self.fullmove_number.to_string()].join(" "))
+        write!(
+            f,
+            "{}",
+            [self.position.to_fen_with(self.shredder), self.halfmove_clock.to_string(), self.fullmove_number.to_string()].join(" ")
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Fen {
+    /// Serializes the FEN as its string representation.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Fen {
+    /// Deserializes an FEN string, returning an error if it is invalid.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fen = String::deserialize(deserializer)?;
+        Self::try_from(fen.as_str()).map_err(serde::de::Error::custom)
     }
 }
@@ -31,18 +31,77 @@ impl fmt::Display for GameResult {
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
 pub enum WinType {
     Checkmate,
-    /// Currently, a loss by timeout is also considered a resignation.
     Resignation,
+    /// Represents a win because the opponent ran out of time.
+    Timeout,
+}
+
+impl WinType {
+    /// Returns every type of win, for building lists of possible results in user interfaces.
+    /// A checkmate is produced by [`Board::make_move`](super::Board::make_move), a resignation by [`Board::resign`](super::Board::resign),
+    /// and a timeout by [`Board::flag`](super::Board::flag).
+    pub fn all() -> [Self; 3] {
+        [Self::Checkmate, Self::Resignation, Self::Timeout]
+    }
 }
 
 /// Represents types of draws.
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DrawType {
     FivefoldRepetition,
     SeventyFiveMoveRule,
+    /// Represents a draw claimed by threefold repetition, using [`Board::claim_draw`](super::Board::claim_draw).
+    ThreefoldRepetition,
+    /// Represents a draw claimed by the fifty-move rule, using [`Board::claim_draw`](super::Board::claim_draw).
+    FiftyMoveRule,
+    /// Represents a stalemate, with the tuple value being the side in stalemate.
+    Stalemate(Color),
+    InsufficientMaterial,
+    Agreement,
+    /// Represents a draw because a side ran out of time while the opponent could not checkmate by any series of legal moves,
+    /// as produced by [`Board::flag`](super::Board::flag).
+    TimeoutVsInsufficientMaterial,
+}
+
+impl DrawType {
+    /// Returns every type of draw (including a stalemate of either side), for building lists of possible results in user interfaces.
+    /// A draw by agreement is produced by [`Board::agree_draw`](super::Board::agree_draw), a draw by threefold repetition or the
+    /// fifty-move rule by [`Board::claim_draw`](super::Board::claim_draw), a draw by timeout vs. insufficient material by
+    /// [`Board::flag`](super::Board::flag), and every other type of draw is detected automatically when a position is reached.
+    pub fn all() -> [Self; 9] {
+        [
+            Self::FivefoldRepetition,
+            Self::SeventyFiveMoveRule,
+            Self::ThreefoldRepetition,
+            Self::FiftyMoveRule,
+            Self::Stalemate(Color::White),
+            Self::Stalemate(Color::Black),
+            Self::InsufficientMaterial,
+            Self::Agreement,
+            Self::TimeoutVsInsufficientMaterial,
+        ]
+    }
+}
+
+/// Represents the way a game has (or has not yet) ended, as returned by [`Board::termination`](super::Board::termination).
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub enum Termination {
+    Ongoing,
+    /// Represents a checkmate, with the tuple value being the side in checkmate.
+    Checkmate(Color),
     /// Represents a stalemate, with the tuple value being the side in stalemate.
     Stalemate(Color),
     InsufficientMaterial,
-    /// Currently, a claimed draw and a draw by timeout vs. insufficient checkmating material are also considered a draw by agreement.
+    SeventyFiveMove,
+    FivefoldRepetition,
+    FiftyMove,
+    ThreefoldRepetition,
+    /// Represents a resignation, with the tuple value being the side that resigned.
+    Resignation(Color),
     Agreement,
+    /// Represents a loss on time, with the tuple value being the side that ran out of time.
+    Timeout(Color),
+    /// Represents a draw by [`DrawType::TimeoutVsInsufficientMaterial`], with the tuple value being the side that ran out of time.
+    TimeoutVsInsufficientMaterial(Color),
 }
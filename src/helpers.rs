@@ -13,14 +13,14 @@ pub fn idx_to_sq(idx: usize) -> (char, char) {
 
 /// Checks whether a long-range piece can move on the axis `axis_direction` from the square `sq`
 pub fn long_range_can_move(sq: usize, axis_direction: isize) -> bool {
-    !(axis_direction == 1 && (sq + 1) % 8 == 0
-        || axis_direction == -1 && sq % 8 == 0
+    !(axis_direction == 1 && (sq + 1).is_multiple_of(8)
+        || axis_direction == -1 && sq.is_multiple_of(8)
         || axis_direction == 8 && sq >= 56
         || axis_direction == -8 && sq < 8
-        || axis_direction == 7 && (sq >= 56 || sq % 8 == 0)
-        || axis_direction == -7 && (sq < 8 || (sq + 1) % 8 == 0)
-        || axis_direction == 9 && (sq >= 56 || (sq + 1) % 8 == 0)
-        || axis_direction == -9 && (sq < 8 || sq % 8 == 0))
+        || axis_direction == 7 && (sq >= 56 || sq.is_multiple_of(8))
+        || axis_direction == -7 && (sq < 8 || (sq + 1).is_multiple_of(8))
+        || axis_direction == 9 && (sq >= 56 || (sq + 1).is_multiple_of(8))
+        || axis_direction == -9 && (sq < 8 || sq.is_multiple_of(8)))
 }
 
 /// Counts the number of pieces on the board identical to the `piece` provided that are within the provided square range.
@@ -31,14 +31,6 @@ where
     find_pieces(piece, rng, content).len()
 }
 
-/// Counts the number of pieces on the board that are within the provided square range.
-pub fn count_pieces<R>(rng: R, content: &[Option<Piece>; 64]) -> usize
-where
-    R: RangeBounds<usize> + Iterator<Item = usize>,
-{
-    rng.fold(0, |acc, sq| if content[sq].is_some() { acc + 1 } else { acc })
-}
-
 /// Finds the indices of all occurrences of a piece identical to the given `piece` on the board in the square range `rng`.
 pub fn find_pieces<R>(piece: Piece, rng: R, content: &[Option<Piece>; 64]) -> Vec<usize>
 where
@@ -48,14 +40,6 @@ where
     rng.filter(|&sq| content[sq] == piece).collect()
 }
 
-/// Finds the indices of all occurrences pieces on the board in the square range `rng`.
-pub fn find_all_pieces<R>(rng: R, content: &[Option<Piece>; 64]) -> Vec<usize>
-where
-    R: RangeBounds<usize> + Iterator<Item = usize>,
-{
-    rng.filter(|&sq| content[sq].is_some()).collect()
-}
-
 /// Checks whether capturing a king is pseudolegal for the specified side in the given position.
 pub fn king_capture_pseudolegal(content: &[Option<Piece>; 64], side: Color) -> bool {
     let enemy_king = find_king(!side, content);
@@ -68,6 +52,43 @@ pub fn king_capture_pseudolegal(content: &[Option<Piece>; 64], side: Color) -> b
     .controls_square(enemy_king, side)
 }
 
+/// Returns the squares strictly between `a` and `b` if they share a rank, file or diagonal (otherwise `None`).
+pub fn squares_between(a: usize, b: usize) -> Option<Vec<usize>> {
+    let (file_diff, rank_diff) = ((b % 8) as isize - (a % 8) as isize, (b / 8) as isize - (a / 8) as isize);
+    if a == b || !(file_diff == 0 || rank_diff == 0 || file_diff.abs() == rank_diff.abs()) {
+        return None;
+    }
+    let step = rank_diff.signum() * 8 + file_diff.signum();
+    let mut squares = Vec::new();
+    let mut sq = a as isize + step;
+    while sq != b as isize {
+        squares.push(sq as usize);
+        sq += step;
+    }
+    Some(squares)
+}
+
+/// Checks whether the piece on the square `src` attacks the square `dest`, regardless of whether capturing on `dest` would be legal.
+pub fn attacks(content: &[Option<Piece>; 64], src: usize, dest: usize) -> bool {
+    let Some(Piece(piece_type, color)) = content[src] else {
+        return false;
+    };
+    let (file_diff, rank_diff) = ((dest % 8) as isize - (src % 8) as isize, (dest / 8) as isize - (src / 8) as isize);
+    match piece_type {
+        PieceType::P => file_diff.abs() == 1 && rank_diff == if color.is_white() { 1 } else { -1 },
+        PieceType::N => matches!((file_diff.abs(), rank_diff.abs()), (1, 2) | (2, 1)),
+        PieceType::K => src != dest && file_diff.abs() <= 1 && rank_diff.abs() <= 1,
+        long_range_type => {
+            let straight = (file_diff == 0) != (rank_diff == 0);
+            let diagonal = file_diff != 0 && file_diff.abs() == rank_diff.abs();
+            if !(straight && long_range_type != PieceType::B || diagonal && long_range_type != PieceType::R) {
+                return false;
+            }
+            squares_between(src, dest).unwrap().into_iter().all(|sq| content[sq].is_none())
+        }
+    }
+}
+
 /// Returns the square index of the king of color `color`.
 pub fn find_king(color: Color, content: &[Option<Piece>; 64]) -> usize {
     content
@@ -82,27 +103,20 @@ pub fn find_king(color: Color, content: &[Option<Piece>; 64]) -> usize {
 pub fn change_content(content: &[Option<Piece>; 64], move_: &Move, castling_rights: &[Option<usize>]) -> [Option<Piece>; 64] {
     let mut content = *content;
     let Move(src, dest, spec) = move_;
+    if let Some(SpecialMoveType::CastlingKingside | SpecialMoveType::CastlingQueenside) = spec {
+        let (rook, rook_dest) = match *dest {
+            6 => (castling_rights[0], 5),
+            2 => (castling_rights[1], 3),
+            62 => (castling_rights[2], 61),
+            58 => (castling_rights[3], 59),
+            _ => panic!("the universe is malfunctioning"),
+        };
+        let (king, rook) = (content[*src].take(), content[rook.unwrap()].take());
+        (content[*dest], content[rook_dest]) = (king, rook);
+        return content;
+    }
     (content[*src], content[*dest]) = (None, content[*src]);
     match spec {
-        Some(SpecialMoveType::CastlingKingside | SpecialMoveType::CastlingQueenside) => match *dest {
-            6 => {
-                let krook = castling_rights[0].unwrap();
-                (content[krook], content[5]) = (None, content[krook]);
-            }
-            2 => {
-                let qrook = castling_rights[1].unwrap();
-                (content[qrook], content[3]) = (None, content[qrook]);
-            }
-            62 => {
-                let krook = castling_rights[2].unwrap();
-                (content[krook], content[61]) = (None, content[krook]);
-            }
-            58 => {
-                let qrook = castling_rights[3].unwrap();
-                (content[qrook], content[59]) = (None, content[qrook]);
-            }
-            _ => panic!("the universe is malfunctioning"),
-        },
         Some(SpecialMoveType::EnPassant) => match dest {
             16..=23 => content[dest + 8] = None,
             40..=47 => content[dest - 8] = None,
@@ -159,3 +173,74 @@ pub fn as_legal(move_: Move, legal: &[Move]) -> Option<Move> {
         None
     }
 }
+
+/// Sorts moves into the documented order of generated legal moves: by source square, then by destination square,
+/// then by promotion piece (queen, rook, bishop and knight).
+pub fn sort_moves(moves: &mut [Move]) {
+    let promotion_rank = |spec: Option<SpecialMoveType>| match spec {
+        Some(SpecialMoveType::Promotion(PieceType::R)) => 1,
+        Some(SpecialMoveType::Promotion(PieceType::B)) => 2,
+        Some(SpecialMoveType::Promotion(PieceType::N)) => 3,
+        _ => 0,
+    };
+    moves.sort_by_key(|m| (m.0, m.1, promotion_rank(m.2)));
+}
+
+/// Joins the whitespace-separated words of `text` with spaces, breaking lines so that none is longer than `width` characters
+/// (unless a single word is). A comment in braces is kept on one line, as if it were a single word.
+pub fn wrap_words(text: &str, width: usize) -> String {
+    let mut words: Vec<String> = Vec::new();
+    for word in text.split_ascii_whitespace() {
+        match words.last_mut() {
+            Some(comment) if comment.starts_with('{') && !comment.contains('}') => {
+                comment.push(' ');
+                comment.push_str(word);
+            }
+            _ => words.push(word.to_owned()),
+        }
+    }
+    let mut lines: Vec<String> = Vec::new();
+    for word in words {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= width => {
+                line.push(' ');
+                line.push_str(&word);
+            }
+            _ => lines.push(word),
+        }
+    }
+    lines.join("\n")
+}
+
+/// Extracts the SAN moves from PGN movetext, skipping move numbers, comments, variations, NAGs, move annotations, standalone
+/// "e.p." markers and result tokens.
+pub fn movetext_sans(text: &str) -> Vec<String> {
+    let mut stripped = String::new();
+    let (mut in_brace_comment, mut in_line_comment, mut variation_depth) = (false, false, 0usize);
+    for c in text.chars() {
+        match c {
+            _ if in_brace_comment => in_brace_comment = c != '}',
+            _ if in_line_comment => in_line_comment = c != '\n',
+            '{' => in_brace_comment = true,
+            ';' => in_line_comment = true,
+            '(' => variation_depth += 1,
+            ')' => variation_depth = variation_depth.saturating_sub(1),
+            _ if variation_depth > 0 => (),
+            _ => stripped.push(c),
+        }
+        if in_brace_comment || in_line_comment || variation_depth > 0 {
+            stripped.push(' ');
+        }
+    }
+    stripped
+        .split_ascii_whitespace()
+        .filter(|token| !(token.starts_with('$') || ["1-0", "0-1", "1/2-1/2", "*", "e.p."].contains(token)))
+        .map(|token| match token.split_once('.') {
+            Some((number, rest)) if number.chars().all(|c| c.is_ascii_digit()) => rest.trim_start_matches('.'),
+            _ => token,
+        })
+        .map(|san| san.trim_end_matches(['!', '?']))
+        .filter(|san| !san.is_empty())
+        .map(|san| san.to_owned())
+        .collect()
+}
@@ -3,6 +3,7 @@
 //! Examples are available on the [GitHub repository page](https://github.com/Python3-8/rschess).
 
 mod board;
+mod epd;
 pub mod errors;
 mod fen;
 mod game_result;
@@ -10,19 +11,29 @@ mod helpers;
 #[cfg(feature = "img")]
 pub mod img;
 mod move_;
+mod opening_book;
 #[cfg(feature = "pgn")]
 pub mod pgn;
+mod pgn_tags;
 mod piece;
 mod position;
+mod position_builder;
+mod time_control;
+mod zobrist;
 
 pub use board::*;
+pub use epd::Epd;
 pub(crate) use errors::*;
 pub use fen::Fen;
 pub use game_result::*;
 pub use move_::*;
+pub use opening_book::OpeningBook;
+pub use pgn_tags::*;
 pub use piece::*;
 pub use position::*;
+pub use position_builder::*;
 use std::{fmt, ops::Not};
+pub use time_control::*;
 
 /// Converts a square index (`0..64`) to a square name, returning an error if the square index is invalid.
 pub fn idx_to_sq(idx: usize) -> Result<(char, char), InvalidSquareIndexError> {
@@ -40,8 +51,18 @@ pub fn sq_to_idx(file: char, rank: char) -> Result<usize, InvalidSquareNameError
     Ok(helpers::sq_to_idx(file, rank))
 }
 
+/// Returns the indices of the squares strictly between two squares that share a rank, file or diagonal, ordered from `a` to `b`.
+/// Returns `None` if the squares are not on a common line, if they are the same square, or if either square index is invalid.
+pub fn squares_between(a: usize, b: usize) -> Option<Vec<usize>> {
+    if a >= 64 || b >= 64 {
+        return None;
+    }
+    helpers::squares_between(a, b)
+}
+
 /// Represents a side/color.
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     White,
     Black,
@@ -57,6 +78,23 @@ impl Color {
     pub fn is_black(&self) -> bool {
         matches!(self, Self::Black)
     }
+
+    /// Returns the index of the color for use with per-color arrays (0 for white, and 1 for black).
+    pub fn index(&self) -> usize {
+        match self {
+            Self::White => 0,
+            Self::Black => 1,
+        }
+    }
+
+    /// Returns the color with the given index (0 for white, and 1 for black), or `None` if the index is neither 0 nor 1.
+    pub fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Self::White),
+            1 => Some(Self::Black),
+            _ => None,
+        }
+    }
 }
 
 impl TryFrom<&str> for Color {
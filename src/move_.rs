@@ -21,6 +21,14 @@ impl Move {
         self.2
     }
 
+    /// Returns the piece type that the move promotes to, if it is a promotion (otherwise `None`).
+    pub fn promotion(&self) -> Option<PieceType> {
+        match self.2 {
+            Some(SpecialMoveType::Promotion(piece_type)) => Some(piece_type),
+            _ => None,
+        }
+    }
+
     /// Creates a `Move` object from its UCI representation.
     pub fn from_uci(uci: &str) -> Result<Self, InvalidUciError> {
         let uci_len = uci.len();
@@ -40,10 +48,10 @@ impl Move {
         let promotion = match promotion {
             Some(p) => Some({
                 let pt = PieceType::try_from(p).map_err(|_| InvalidUciError::InvalidPieceType(p))?;
-                if pt == PieceType::K {
-                    return Err(InvalidUciError::InvalidPieceType(p));
-                } else {
+                if pt.is_valid_promotion() {
                     pt
+                } else {
+                    return Err(InvalidUciError::InvalidPieceType(p));
                 }
             }),
             _ => None,
@@ -59,6 +67,7 @@ impl Move {
     }
 
     /// Returns the UCI representation of the move.
+    /// Castling is written with the king's destination square; use [`Position::move_to_uci`](super::Position::move_to_uci) for Chess960 castling.
     pub fn to_uci(&self) -> String {
         let ((srcf, srcr), (destf, destr)) = (helpers::idx_to_sq(self.0), helpers::idx_to_sq(self.1));
         format!(
@@ -78,6 +87,24 @@ impl fmt::Display for Move {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Move {
+    /// Serializes the move as its UCI representation.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_uci())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Move {
+    /// Deserializes a move from its UCI representation, returning an error if it is invalid. As with [`Move::from_uci`],
+    /// the move is of type [`SpecialMoveType::Unclear`] unless it is a promotion.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let uci = String::deserialize(deserializer)?;
+        Self::from_uci(&uci).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Represents types of special moves (castling/promotion/en passant).
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
 pub enum SpecialMoveType {
@@ -0,0 +1,39 @@
+use super::{Move, Position};
+use std::collections::HashMap;
+
+/// Represents an opening book, mapping positions to the moves recommended in them.
+/// Positions are looked up regardless of an en passant target square when no en passant capture is possible.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct OpeningBook {
+    entries: HashMap<Position, Vec<Move>>,
+}
+
+impl OpeningBook {
+    /// Creates an empty opening book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a move to the book for the given position, if it is not already present. Moves are kept in the order they were added.
+    pub fn insert(&mut self, position: &Position, move_: Move) {
+        let moves = self.entries.entry(position.with_normalized_ep()).or_default();
+        if !moves.contains(&move_) {
+            moves.push(move_);
+        }
+    }
+
+    /// Returns the book moves for the given position, in the order they were added (an empty slice if the position is not in the book).
+    pub fn moves(&self, position: &Position) -> &[Move] {
+        self.entries.get(&position.with_normalized_ep()).map(|v| v.as_slice()).unwrap_or_default()
+    }
+
+    /// Returns the number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether the book is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::{Datelike, NaiveDate};
+use std::{collections::HashMap, fmt};
+
+/// Represents the tags of a PGN document, as used by [`Board::to_pgn`](super::Board::to_pgn).
+/// Absent tags of the [Seven Tag Roster](https://en.wikipedia.org/wiki/Portable_Game_Notation#Seven_Tag_Roster) are written as
+/// "?" (or "????.??.??" for the date), as recommended by the PGN specification. The _Result_ tag is always derived from the game state.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct PgnTags {
+    /// The name of the tournament or match event
+    pub event: Option<String>,
+    /// The location of the event
+    pub site: Option<String>,
+    /// The starting date of the game
+    pub date: Option<NaiveDate>,
+    /// The playing round of the game within the event
+    pub round: Option<String>,
+    /// The player of the white pieces
+    pub white: Option<String>,
+    /// The player of the black pieces
+    pub black: Option<String>,
+    /// The ECO code of the opening, which is omitted if absent
+    pub eco: Option<String>,
+    /// Any other tags, which are written after the others in alphabetical order of their names
+    pub extra: HashMap<String, String>,
+}
+
+impl PgnTags {
+    /// Returns the tag pairs in the order they are written, with absent Seven Tag Roster values replaced by their defaults.
+    /// The _Result_ tag is included in its place in the roster if `result` is given.
+    pub(crate) fn tag_pairs(&self, result: Option<&str>) -> Vec<(String, String)> {
+        let or_unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| "?".to_owned());
+        let mut tag_pairs = vec![
+            ("Event".to_owned(), or_unknown(&self.event)),
+            ("Site".to_owned(), or_unknown(&self.site)),
+            (
+                "Date".to_owned(),
+                self.date.map_or_else(|| "????.??.??".to_owned(), |d| format!("{:04}.{:02}.{:02}", d.year(), d.month(), d.day())),
+            ),
+            ("Round".to_owned(), or_unknown(&self.round)),
+            ("White".to_owned(), or_unknown(&self.white)),
+            ("Black".to_owned(), or_unknown(&self.black)),
+        ];
+        if let Some(result) = result {
+            tag_pairs.push(("Result".to_owned(), result.to_owned()));
+        }
+        if let Some(eco) = &self.eco {
+            tag_pairs.push(("ECO".to_owned(), eco.clone()));
+        }
+        let mut extra: Vec<_> = self.extra.iter().map(|(name, value)| (name.clone(), value.clone())).collect();
+        extra.sort();
+        tag_pairs.extend(extra);
+        tag_pairs
+    }
+
+    /// Splits PGN text into its tag pairs and the remaining movetext, returning the first malformed tag pair as an error.
+    pub(crate) fn parse_tag_section(text: &str) -> Result<(HashMap<String, String>, &str), String> {
+        let mut tag_pairs = HashMap::new();
+        let mut rest = text.trim_start();
+        while let Some(tag) = rest.strip_prefix('[') {
+            let malformed = || rest.lines().next().unwrap_or_default().to_owned();
+            let name_len = tag.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(tag.len());
+            let (name, tag) = tag.split_at(name_len);
+            let Some(tag) = tag.trim_start().strip_prefix('"') else {
+                return Err(malformed());
+            };
+            let (mut value, mut chars) = (String::new(), tag.char_indices());
+            let end = loop {
+                match chars.next() {
+                    Some((_, '\\')) => value.extend(chars.next().map(|(_, c)| c)),
+                    Some((i, '"')) => break i,
+                    Some((_, c)) => value.push(c),
+                    None => return Err(malformed()),
+                }
+            };
+            let Some(tag) = tag[end + 1..].trim_start().strip_prefix(']') else {
+                return Err(malformed());
+            };
+            if name.is_empty() {
+                return Err(malformed());
+            }
+            tag_pairs.insert(name.to_owned(), value);
+            rest = tag.trim_start();
+        }
+        Ok((tag_pairs, rest))
+    }
+
+    /// Formats a tag pair as a line of PGN, escaping backslashes and quotes in the value.
+    pub(crate) fn format_tag_pair(name: &str, value: &str) -> String {
+        format!(r#"[{name} "{}"]"#, value.replace('\\', r"\\").replace('"', r#"\""#))
+    }
+}
+
+impl fmt::Display for PgnTags {
+    /// Formats the tags as PGN tag pairs, one per line, escaping backslashes and quotes in the values.
+    /// The _Result_ tag is not included, as it is derived from the game state by [`Board::to_pgn`](super::Board::to_pgn).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<_> = self.tag_pairs(None).iter().map(|(name, value)| Self::format_tag_pair(name, value)).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
@@ -3,6 +3,7 @@ use std::{collections::HashMap, fmt};
 
 /// Represents a piece in the format (_piece type_, _color_).
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Piece(pub(crate) PieceType, pub(crate) Color);
 
 impl Piece {
@@ -54,6 +55,7 @@ impl fmt::Display for Piece {
 
 /// Represents types of pieces.
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PieceType {
     K,
     Q,
@@ -63,6 +65,24 @@ pub enum PieceType {
     P,
 }
 
+impl PieceType {
+    /// Checks whether a pawn can promote to this type of piece (a knight, bishop, rook or queen).
+    pub fn is_valid_promotion(&self) -> bool {
+        matches!(self, Self::N | Self::B | Self::R | Self::Q)
+    }
+
+    /// Returns the conventional material value of this type of piece: 1 for a pawn, 3 for a knight or bishop, 5 for a rook, 9 for a queen and 0 for a king.
+    pub fn value(&self) -> i32 {
+        match self {
+            Self::P => 1,
+            Self::N | Self::B => 3,
+            Self::R => 5,
+            Self::Q => 9,
+            Self::K => 0,
+        }
+    }
+}
+
 impl TryFrom<char> for PieceType {
     type Error = InvalidPieceCharacterError;
 
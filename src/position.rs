@@ -1,4 +1,4 @@
-use super::{helpers, Color, IllegalMoveError, InvalidSanMoveError, Move, Piece, PieceType, SpecialMoveType};
+use super::{helpers, zobrist, Color, IllegalMoveError, InvalidLanMoveError, InvalidSanMoveError, Move, Piece, PieceType, SpecialMoveType};
 use std::{
     collections::HashMap,
     fmt,
@@ -27,6 +27,11 @@ pub struct Position {
 impl Position {
     /// Generates an FEN string representing the board data, active color, castling rights, and en passant target in the position.
     pub fn to_fen(&self) -> String {
+        self.to_fen_with(false)
+    }
+
+    /// Generates the FEN fields of the position, writing every castling right as a Shredder-FEN file letter if `shredder` is set.
+    pub(crate) fn to_fen_with(&self, shredder: bool) -> String {
         let Self {
             content,
             side,
@@ -60,33 +65,33 @@ impl Position {
         let active_color = char::from(*side).to_string();
         let mut castling_availability = String::new();
         let count_rooks = |rng, color| helpers::count_piece(rng, Piece(PieceType::R, color), content);
-        let (wk, bk) = (helpers::find_king(Color::White, content), helpers::find_king(Color::Black, content));
+        let (wk, bk) = (|| helpers::find_king(Color::White, content), || helpers::find_king(Color::Black, content));
         if castling_rights[0].is_some() {
-            castling_availability.push(if count_rooks(wk + 1..8, Color::White) == 1 {
+            castling_availability.push(if !shredder && count_rooks(wk() + 1..8, Color::White) == 1 {
                 'K'
             } else {
                 helpers::idx_to_sq(castling_rights[0].unwrap()).0.to_ascii_uppercase()
             });
         }
         if castling_rights[1].is_some() {
-            castling_availability.push(if count_rooks(0..wk, Color::White) == 1 {
+            castling_availability.push(if !shredder && count_rooks(0..wk(), Color::White) == 1 {
                 'Q'
             } else {
                 helpers::idx_to_sq(castling_rights[1].unwrap()).0.to_ascii_uppercase()
             });
         }
         if castling_rights[2].is_some() {
-            castling_availability.push(if count_rooks(bk + 1..64, Color::Black) == 1 {
+            castling_availability.push(if !shredder && count_rooks(bk() + 1..64, Color::Black) == 1 {
                 'k'
             } else {
                 helpers::idx_to_sq(castling_rights[2].unwrap()).0
             });
         }
         if castling_rights[3].is_some() {
-            castling_availability.push(if count_rooks(56..bk, Color::Black) == 1 {
+            castling_availability.push(if !shredder && count_rooks(56..bk(), Color::Black) == 1 {
                 'q'
             } else {
-                helpers::idx_to_sq(castling_rights[2].unwrap()).0
+                helpers::idx_to_sq(castling_rights[3].unwrap()).0
             });
         }
         if castling_availability.is_empty() {
@@ -231,53 +236,247 @@ impl Position {
         ))
     }
 
+    /// Converts a `Move` to SAN without a trailing check or checkmate symbol (e.g. "Qh5" instead of "Qh5#"), returning an error if the move is illegal.
+    pub fn move_to_san_plain(&self, move_: Move) -> Result<String, IllegalMoveError> {
+        Ok(self.move_to_san(move_)?.trim_end_matches(['+', '#']).to_owned())
+    }
+
+    /// Converts a `Move` to long algebraic notation (e.g. "Ng1-f3", "e5xd6" or "e7-e8=Q+"), returning an error if the move is illegal.
+    /// Like SAN, castling is written as "O-O" or "O-O-O", and check or checkmate is indicated by a trailing '+' or '#'.
+    pub fn move_to_lan(&self, move_: Move) -> Result<String, IllegalMoveError> {
+        let move_ = helpers::as_legal(move_, &self.gen_non_illegal_moves()).ok_or(IllegalMoveError(move_))?;
+        let Move(src, dest, spec) = move_;
+        let new_position = self.with_move_made(move_).unwrap();
+        let suffix = if new_position.is_checkmate() {
+            "#"
+        } else if new_position.is_check() {
+            "+"
+        } else {
+            ""
+        };
+        let body = match spec {
+            Some(SpecialMoveType::CastlingKingside) => "O-O".to_owned(),
+            Some(SpecialMoveType::CastlingQueenside) => "O-O-O".to_owned(),
+            _ => {
+                let ((srcf, srcr), (destf, destr)) = (helpers::idx_to_sq(src), helpers::idx_to_sq(dest));
+                let piece = match self.content[src] {
+                    Some(Piece(PieceType::P, _)) => String::new(),
+                    Some(Piece(pt, _)) => char::from(pt).to_string(),
+                    None => panic!("the universe is malfunctioning"),
+                };
+                let separator = if self.content[dest].is_some() || spec == Some(SpecialMoveType::EnPassant) { 'x' } else { '-' };
+                let promotion = match spec {
+                    Some(SpecialMoveType::Promotion(piece_type)) => format!("={}", char::from(piece_type)),
+                    _ => String::new(),
+                };
+                format!("{piece}{srcf}{srcr}{separator}{destf}{destr}{promotion}")
+            }
+        };
+        Ok(format!("{body}{suffix}"))
+    }
+
+    /// Converts a `Move` to UCI, returning an error if the move is illegal.
+    /// Castling is written with the king's destination square (e.g. "e1g1") when the king starts on the e-file and the rook in the corner,
+    /// and otherwise as the king capturing its own rook (e.g. "g1h1" or "e1b1"), as expected by engines in `UCI_Chess960` mode.
+    pub fn move_to_uci(&self, move_: Move) -> Result<String, IllegalMoveError> {
+        let move_ = helpers::as_legal(self.resolve_king_onto_rook(move_), &self.gen_non_illegal_moves()).ok_or(IllegalMoveError(move_))?;
+        let (offset, home, corners) = if self.side.is_white() { (0, 4, [7, 0]) } else { (2, 60, [63, 56]) };
+        let rook_index = match move_.2 {
+            Some(SpecialMoveType::CastlingKingside) => 0,
+            Some(SpecialMoveType::CastlingQueenside) => 1,
+            _ => return Ok(move_.to_uci()),
+        };
+        let rook = self.castling_rights[offset + rook_index].unwrap();
+        Ok(if move_.0 == home && rook == corners[rook_index] {
+            move_.to_uci()
+        } else {
+            Move(move_.0, rook, None).to_uci()
+        })
+    }
+
+    /// Constructs a `Move` from a long algebraic notation representation, returning an error if it is invalid or illegal.
+    /// Either '-' or 'x' may separate the squares regardless of whether the move is a capture, castling may be written with
+    /// zeros, and trailing move annotations and check or checkmate symbols are ignored.
+    pub fn lan_to_move(&self, lan: &str) -> Result<Move, InvalidLanMoveError> {
+        let normalized = lan.trim().trim_end_matches(['!', '?']).trim_end_matches(['+', '#']);
+        let normalized = if normalized.chars().all(|c| matches!(c, '0' | 'O' | '-')) {
+            normalized.replace('0', "O")
+        } else {
+            normalized.replace('x', "-")
+        };
+        self.gen_non_illegal_moves()
+            .into_iter()
+            .find(|&m| self.move_to_lan(m).unwrap().trim_end_matches(['+', '#']).replace('x', "-") == normalized)
+            .ok_or(InvalidLanMoveError(lan.to_owned()))
+    }
+
     /// Constructs a `Move` from a SAN representation, returning an error if it is invalid or illegal.
+    /// Common variants of SAN are accepted: castling may be written with zeros (e.g. "0-0-0"), and trailing move annotations
+    /// (like '!?'), an "e.p." marker and check or checkmate symbols are ignored, whether or not they are accurate.
     pub fn san_to_move(&self, san: &str) -> Result<Move, InvalidSanMoveError> {
-        let san = san.trim().replace('0', "O").replace(['+', '#'], "");
+        let normalized = san.trim().trim_end_matches(['!', '?']).trim_end_matches(['+', '#']);
+        let normalized = normalized.strip_suffix("e.p.").unwrap_or(normalized).trim_end();
+        let normalized = if normalized.chars().all(|c| matches!(c, '0' | 'O' | '-')) {
+            normalized.replace('0', "O")
+        } else {
+            normalized.to_owned()
+        };
         self.gen_non_illegal_moves()
             .into_iter()
-            .find(|&m| self.move_to_san(m).unwrap().replace(['+', '#'], "") == san)
+            .find(|&m| self.move_to_san(m).unwrap().trim_end_matches(['+', '#']) == normalized)
             .ok_or(InvalidSanMoveError(san.to_owned()))
     }
 
     /// Returns the position which would occur if the given move were played, returning an error if the move is illegal.
     pub fn with_move_made(&self, move_: Move) -> Result<Self, IllegalMoveError> {
-        let move_ = match helpers::as_legal(move_, &self.gen_non_illegal_moves()) {
-            Some(m) => m,
-            _ => return Err(IllegalMoveError(move_)),
-        };
+        match helpers::as_legal(move_, &self.gen_non_illegal_moves()) {
+            Some(m) => Ok(self.with_legal_move_made(m)),
+            _ => Err(IllegalMoveError(move_)),
+        }
+    }
+
+    /// Returns the position which would occur if the given move, which must be legal and fully specified, were played.
+    pub(crate) fn with_legal_move_made(&self, move_: Move) -> Self {
+        let mut position = self.clone();
+        position.make_legal_move_in_place(move_);
+        position
+    }
+
+    /// Plays the given move, which must be legal and fully specified, on this position.
+    fn make_legal_move_in_place(&mut self, move_: Move) {
         let castling_rights_idx_offset = if self.side.is_white() { 0 } else { 2 };
+        let old_castling_rights = self.castling_rights;
         let Self {
             content,
-            mut side,
-            mut castling_rights,
-            ..
+            side,
+            castling_rights,
+            ep_target,
         } = self;
-        let mut ep_target = None;
+        *ep_target = None;
         let Move(move_src, move_dest, ..) = move_;
         let moved_piece = content[move_src];
         match moved_piece {
             Some(Piece(PieceType::K, _)) => (castling_rights[castling_rights_idx_offset], castling_rights[castling_rights_idx_offset + 1]) = (None, None),
-            Some(Piece(PieceType::P, _)) => {
-                if (std::cmp::max(move_src, move_dest) - std::cmp::min(move_src, move_dest)) == 16 {
-                    ep_target = Some(if side.is_white() { move_src + 8 } else { move_src - 8 });
-                }
+            Some(Piece(PieceType::P, _)) if (std::cmp::max(move_src, move_dest) - std::cmp::min(move_src, move_dest)) == 16 => {
+                *ep_target = Some(if side.is_white() { move_src + 8 } else { move_src - 8 });
             }
             _ => (),
         }
         for maybe_rook in [move_src, move_dest] {
             let maybe_right = castling_rights.iter().enumerate().find(|(_, right)| right.is_some() && right.unwrap() == maybe_rook);
-            if maybe_right.is_some() {
-                castling_rights[maybe_right.unwrap().0] = None;
+            if let Some((i, _)) = maybe_right {
+                castling_rights[i] = None;
             }
         }
-        side = !side;
-        let new_content = helpers::change_content(content, &move_, &self.castling_rights);
-        Ok(Self {
-            content: new_content,
-            side,
-            castling_rights,
-            ep_target,
+        *side = !*side;
+        *content = helpers::change_content(content, &move_, &old_castling_rights);
+    }
+
+    /// Plays the given move on this position in place, returning an [`Undo`] token that [`Position::unmake_move`] can use to
+    /// restore the previous position, or an error if the move is illegal. Unlike [`Position::with_move_made`], this does not
+    /// copy the position or generate every legal move, which makes it suitable for search loops. Move counters are not part of
+    /// a position, so callers that track the halfmove clock must restore it themselves.
+    pub fn make_move_in_place(&mut self, move_: Move) -> Result<Undo, IllegalMoveError> {
+        let move_ = helpers::as_legal(move_, &self.legal_moves_from(move_.0)).ok_or(IllegalMoveError(move_))?;
+        let undo = self.undo_for(move_);
+        self.make_legal_move_in_place(move_);
+        Ok(undo)
+    }
+
+    /// Records the state that the given legal, fully specified move would change.
+    fn undo_for(&self, move_: Move) -> Undo {
+        let Move(src, dest, spec) = move_;
+        let (extra_a, extra_b) = match spec {
+            Some(SpecialMoveType::CastlingKingside) => (self.castling_rights[if self.side.is_white() { 0 } else { 2 }].unwrap(), dest - 1),
+            Some(SpecialMoveType::CastlingQueenside) => (self.castling_rights[if self.side.is_white() { 1 } else { 3 }].unwrap(), dest + 1),
+            Some(SpecialMoveType::EnPassant) => (if self.side.is_white() { dest - 8 } else { dest + 8 }, src),
+            _ => (src, src),
+        };
+        Undo {
+            squares: [src, dest, extra_a, extra_b].map(|sq| (sq, self.content[sq])),
+            castling_rights: self.castling_rights,
+            ep_target: self.ep_target,
+        }
+    }
+
+    /// Restores the position from before the move that returned the given [`Undo`] token.
+    /// The token must come from the last move made in place on this position.
+    pub fn unmake_move(&mut self, undo: Undo) {
+        for (sq, occupant) in undo.squares {
+            self.content[sq] = occupant;
+        }
+        self.side = !self.side;
+        self.castling_rights = undo.castling_rights;
+        self.ep_target = undo.ep_target;
+    }
+
+    /// Returns the position mirrored vertically with the colors of all pieces swapped, so that white's pieces become
+    /// black's and vice versa. The side to move, castling rights and en passant target square are transformed accordingly.
+    pub fn color_flipped(&self) -> Self {
+        let mut content = [None; 64];
+        for (sq, occupant) in self.content.iter().enumerate() {
+            content[sq ^ 56] = occupant.map(|Piece(piece_type, color)| Piece(piece_type, !color));
+        }
+        let flip = |sq: Option<usize>| sq.map(|sq| sq ^ 56);
+        let [wk, wq, bk, bq] = self.castling_rights;
+        Self {
+            content,
+            side: !self.side,
+            castling_rights: [flip(bk), flip(bq), flip(wk), flip(wq)],
+            ep_target: flip(self.ep_target),
+        }
+    }
+
+    /// Returns the position rotated by 180 degrees with the colors of all pieces swapped, i.e. mirrored both vertically and
+    /// horizontally, so that white's pieces become black's and vice versa. The side to move is swapped and the en passant target
+    /// square is transformed accordingly. Castling rights are kept for the same rooks, which swap sides relative to their king
+    /// (e.g. white's kingside rook on h1 becomes black's queenside rook on a8); since the kings move to the d-file, castling
+    /// then follows Chess960 rules.
+    pub fn rotate_180(&self) -> Self {
+        let mut content = [None; 64];
+        for (sq, occupant) in self.content.iter().enumerate() {
+            content[sq ^ 63] = occupant.map(|Piece(piece_type, color)| Piece(piece_type, !color));
+        }
+        let rotate = |sq: Option<usize>| sq.map(|sq| sq ^ 63);
+        let [wk, wq, bk, bq] = self.castling_rights;
+        Self {
+            content,
+            side: !self.side,
+            castling_rights: [rotate(bq), rotate(bk), rotate(wq), rotate(wk)],
+            ep_target: rotate(self.ep_target),
+        }
+    }
+
+    /// Checks whether two positions are identical up to a symmetry of the board. The symmetries applied are:
+    /// * Swapping the colors (as in [`Position::color_flipped`]), which is always applied
+    /// * Mirroring horizontally (swapping the a-file and the h-file, and so on), only if neither side has any castling rights,
+    ///   since castling is not symmetric between the kingside and the queenside
+    /// * Mirroring vertically without swapping the colors, only if there are no pawns and neither side has any castling rights,
+    ///   since pawns move in one direction only
+    ///
+    /// An en passant target square is ignored if no legal en passant capture is possible, and is transformed along with the board otherwise.
+    pub fn equivalent_under_symmetry(&self, other: &Position) -> bool {
+        let (this, other) = (self.with_normalized_ep(), other.with_normalized_ep());
+        let no_castling = this.castling_rights == [None; 4];
+        let pawnless = !this.content.iter().any(|o| matches!(o, Some(Piece(PieceType::P, _))));
+        let mut masks = vec![0];
+        if no_castling {
+            masks.push(7);
+            if pawnless {
+                masks.extend([56, 63]);
+            }
+        }
+        masks.into_iter().any(|mask| {
+            let mut content = [None; 64];
+            for (sq, &occupant) in this.content.iter().enumerate() {
+                content[sq ^ mask] = occupant;
+            }
+            let transformed = Self {
+                content,
+                ep_target: this.ep_target.map(|sq| sq ^ mask),
+                ..this.clone()
+            };
+            transformed == other || transformed.color_flipped() == other
         })
     }
 
@@ -285,6 +484,13 @@ impl Position {
     /// If `ascii` is `true`, this function uses piece characters like 'K' and 'p' instead of
     /// characters like '♔' and '♟'.
     pub fn pretty_print(&self, perspective: Color, ascii: bool) -> String {
+        self.pretty_print_opts(perspective, PrintOptions { ascii, ..Default::default() })
+    }
+
+    /// Pretty-prints the position to a string, from the perspective of the side `perspective`, using the given `PrintOptions`.
+    /// The default options produce the same output as [`Position::pretty_print`] with `ascii` set to `false`.
+    pub fn pretty_print_opts(&self, perspective: Color, options: PrintOptions) -> String {
+        let PrintOptions { ascii, coordinates } = options;
         let mut string = String::new();
         let mut content = self.content;
         let ranks: Vec<_> = if perspective.is_white() {
@@ -297,12 +503,16 @@ impl Position {
         if perspective.is_black() {
             file_names.reverse();
         }
-        string += &("  ".to_owned() + "┌" + &"───┬".repeat(7) + "───┐\n");
+        let margin = if coordinates { "  " } else { "" };
+        string += &(margin.to_owned() + "┌" + &"───┬".repeat(7) + "───┐\n");
         for (ranki, rank) in ranks {
             if ranki != 0 {
-                string += &("  ".to_owned() + "├" + &"───┼".repeat(7) + "───┤\n");
+                string += &(margin.to_owned() + "├" + &"───┼".repeat(7) + "───┤\n");
+            }
+            if coordinates {
+                string += &format!("{} ", if perspective.is_white() { 8 - ranki } else { ranki + 1 });
             }
-            string += &format!("{} │", if perspective.is_white() { 8 - ranki } else { ranki + 1 },);
+            string.push('│');
             for occupant in rank.iter() {
                 string += &format!(
                     " {} │",
@@ -319,28 +529,167 @@ impl Position {
             }
             string.push('\n');
         }
-        string += &("  ".to_owned() + "└" + &"───┴".repeat(7) + "───┘\n");
-        let mut files = vec![" "];
-        files.extend(file_names);
-        string += &(files.join("   ") + "  ");
+        string += &(margin.to_owned() + "└" + &"───┴".repeat(7) + "───┘");
+        if coordinates {
+            let mut files = vec![" "];
+            files.extend(file_names);
+            string += &("\n".to_owned() + &files.join("   ") + "  ");
+        }
         string
     }
 
     /// Generates the legal moves in the position, assuming the game is ongoing.
+    /// When the side to move is in check, the moves are generated with [`Position::gen_check_evasions`].
+    ///
+    /// The moves are ordered by source square, then by destination square, and then by promotion piece
+    /// (queen, rook, bishop and knight). This order is stable and can be relied upon.
     pub fn gen_non_illegal_moves(&self) -> Vec<Move> {
         if let Some(v) = legal_move_cache().lock().unwrap().get(self) {
             return v.clone();
         }
-        let v = (0..64).fold(Vec::new(), |v, i| [v, self.gen_non_illegal_moves_sq(i)].concat());
+        let v = self.gen_non_illegal_moves_uncached();
         legal_move_cache().lock().unwrap().insert(self.clone(), v.clone());
         v
     }
 
+    /// Returns an iterator over the legal moves in the position, in the same order as [`Position::gen_non_illegal_moves`].
+    /// Unless the moves of the position have already been generated, they are generated lazily one square at a time,
+    /// which makes this cheaper when only the first few moves are needed (e.g. to check whether any legal move exists).
+    pub fn legal_moves_iter(&self) -> impl Iterator<Item = Move> + '_ {
+        let cached = legal_move_cache().lock().unwrap().get(self).cloned();
+        let lazy = cached.is_none();
+        cached.into_iter().flatten().chain((0..64).filter(move |_| lazy).flat_map(|sq| self.gen_non_illegal_moves_sq(sq)))
+    }
+
+    /// Generates the legal moves in the position like [`Position::gen_non_illegal_moves`], without consulting or filling the cache.
+    pub(crate) fn gen_non_illegal_moves_uncached(&self) -> Vec<Move> {
+        match self.gen_check_evasions() {
+            Some(v) => v,
+            None => (0..64).fold(Vec::new(), |v, i| [v, self.gen_non_illegal_moves_sq(i)].concat()),
+        }
+    }
+
+    /// Counts the leaf nodes of the legal move tree of the given depth, without caching the generated moves.
+    pub(crate) fn perft(&self, depth: usize) -> u64 {
+        self.clone().perft_in_place(depth)
+    }
+
+    /// Counts the leaf nodes like [`Position::perft`], making and unmaking moves on this position.
+    fn perft_in_place(&mut self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.gen_non_illegal_moves_uncached();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut nodes = 0;
+        for move_ in moves {
+            let undo = self.undo_for(move_);
+            self.make_legal_move_in_place(move_);
+            nodes += self.perft_in_place(depth - 1);
+            self.unmake_move(undo);
+        }
+        nodes
+    }
+
+    /// Generates the legal moves of the side to move if it is in check, returning `None` if it is not in check.
+    ///
+    /// Rather than testing every pseudolegal move, only king moves, captures of the checking piece and (for a check by a
+    /// long-range piece) moves that block the check are considered. In double check, only king moves are generated, as no
+    /// other move can resolve both checks. The moves are returned in the same order as [`Position::gen_non_illegal_moves`].
+    pub fn gen_check_evasions(&self) -> Option<Vec<Move>> {
+        let Self { content, side, castling_rights, .. } = self;
+        let king = helpers::find_king(*side, content);
+        let checkers = self.attackers_of(king, !*side);
+        let &[checker] = checkers.as_slice() else {
+            return if checkers.is_empty() { None } else { Some(self.gen_non_illegal_moves_sq(king)) };
+        };
+        let mut targets = helpers::squares_between(king, checker).unwrap_or_default();
+        targets.push(checker);
+        let ep_victim = |dest: usize| if side.is_white() { dest - 8 } else { dest + 8 };
+        let mut evasions = Vec::new();
+        for i in 0..64 {
+            if i == king {
+                evasions.append(&mut self.gen_non_illegal_moves_sq(king));
+                continue;
+            }
+            let mut moves: Vec<_> = self
+                .gen_pseudolegal_moves_sq(i)
+                .into_iter()
+                .filter(|move_| {
+                    (targets.contains(&move_.1) || move_.2 == Some(SpecialMoveType::EnPassant) && ep_victim(move_.1) == checker)
+                        && !helpers::king_capture_pseudolegal(&helpers::change_content(content, move_, castling_rights), !*side)
+                })
+                .collect();
+            helpers::sort_moves(&mut moves);
+            evasions.append(&mut moves);
+        }
+        Some(evasions)
+    }
+
+    /// Converts a king move onto one of its own castling rooks (as used by UCI in Chess960) to the corresponding castling move,
+    /// returning any other move unchanged.
+    pub(crate) fn resolve_king_onto_rook(&self, move_: Move) -> Move {
+        let Move(src, dest, spec) = move_;
+        if !matches!(spec, None | Some(SpecialMoveType::Unclear)) || src >= 64 || self.content[src] != Some(Piece(PieceType::K, self.side)) {
+            return move_;
+        }
+        let (offset, oo_sq, ooo_sq) = if self.side.is_white() { (0, 6, 2) } else { (2, 62, 58) };
+        if self.castling_rights[offset] == Some(dest) {
+            Move(src, oo_sq, Some(SpecialMoveType::CastlingKingside))
+        } else if self.castling_rights[offset + 1] == Some(dest) {
+            Move(src, ooo_sq, Some(SpecialMoveType::CastlingQueenside))
+        } else {
+            move_
+        }
+    }
+
+    /// Checks whether a move is legal in the position, assuming the game is ongoing, without generating every legal move.
+    /// For knights, bishops, rooks, queens and ordinary king moves, the move pattern and path are checked directly, followed
+    /// by whether the move leaves the king in check; for pawn moves and possible castling moves, only the moves of the piece on
+    /// the source square are generated. The result is always the same as checking whether [`Position::gen_non_illegal_moves`] contains the move
+    /// (with a move of type [`SpecialMoveType::Unclear`] resolved as in [`Board::is_legal`](super::Board::is_legal)).
+    pub fn is_move_legal(&self, move_: Move) -> bool {
+        let Self { content, side, castling_rights, .. } = self;
+        let Move(src, dest, spec) = move_;
+        if src >= 64 || dest >= 64 {
+            return false;
+        }
+        let Some(Piece(piece_type, color)) = content[src] else {
+            return false;
+        };
+        if color != *side {
+            return false;
+        }
+        let pattern_valid = helpers::attacks(content, src, dest);
+        match piece_type {
+            PieceType::P => return helpers::as_legal(move_, &self.gen_non_illegal_moves_sq(src)).is_some(),
+            PieceType::K if spec.is_some() || !pattern_valid => return helpers::as_legal(move_, &self.gen_non_illegal_moves_sq(src)).is_some(),
+            _ if !matches!(spec, None | Some(SpecialMoveType::Unclear)) || !pattern_valid => return false,
+            _ => (),
+        }
+        !matches!(content[dest], Some(Piece(_, c)) if c == *side) && !helpers::king_capture_pseudolegal(&helpers::change_content(content, &Move(src, dest, None), castling_rights), !*side)
+    }
+
+    /// Returns the legal moves of the piece on a specific square, assuming the game is ongoing, for user interfaces that
+    /// have already picked up a piece. If the legal moves of the position have already been generated, they are filtered
+    /// by origin; otherwise only the moves of that piece are generated. The result is always the same as filtering
+    /// [`Position::gen_non_illegal_moves`] by origin.
+    /// The square index `sq` can be converted from a square name using the [`sq_to_idx`](super::sq_to_idx) function.
+    pub fn legal_moves_from(&self, sq: usize) -> Vec<Move> {
+        if let Some(v) = legal_move_cache().lock().unwrap().get(self) {
+            return v.iter().copied().filter(|m| m.0 == sq).collect();
+        }
+        self.gen_non_illegal_moves_sq(sq)
+    }
+
     /// Generates the legal moves **from** a specific square, assuming the game is ongoing.
     /// The square index `i` can be converted from a square name using the [`sq_to_idx`](super::sq_to_idx) function.
     pub fn gen_non_illegal_moves_sq(&self, i: usize) -> Vec<Move> {
         let Self { content, side, castling_rights, .. } = self;
-        self.gen_pseudolegal_moves_sq(i)
+        let mut moves = self
+            .gen_pseudolegal_moves_sq(i)
             .into_iter()
             .filter(|move_| {
                 if let Move(src, dest, Some(SpecialMoveType::CastlingKingside | SpecialMoveType::CastlingQueenside)) = move_ {
@@ -349,16 +698,17 @@ impl Position {
                             return false;
                         }
                     }
-                    return true;
                 }
                 !helpers::king_capture_pseudolegal(&helpers::change_content(content, move_, castling_rights), !*side)
             })
-            .collect()
+            .collect::<Vec<_>>();
+        helpers::sort_moves(&mut moves);
+        moves
     }
 
     /// Checks whether the game is drawn by stalemate. Use [`Position::stalemated_side`] to know which side is in stalemate.
     pub fn is_stalemate(&self) -> bool {
-        !self.is_check() && self.gen_non_illegal_moves().is_empty()
+        !self.is_check() && self.legal_moves_iter().next().is_none()
     }
 
     /// Checks whether any side is in check (a checkmate is also considered a check). Use [`Position::checked_side`] to know which side is in check.
@@ -368,7 +718,17 @@ impl Position {
 
     /// Checks whether any side is in checkmate. Use [`Position::checkmated_side`] to know which side is in checkmate.
     pub fn is_checkmate(&self) -> bool {
-        self.is_check() && self.gen_non_illegal_moves().is_empty()
+        self.is_check() && self.legal_moves_iter().next().is_none()
+    }
+
+    /// Checks whether the position is quiet, i.e. the side to move is not in check and has no legal captures or promotions.
+    /// Quiet positions are suitable for static evaluation, and this is the usual terminal condition of a quiescence search.
+    pub fn is_quiet(&self) -> bool {
+        !self.is_check()
+            && self
+                .gen_non_illegal_moves()
+                .into_iter()
+                .all(|m| !matches!(m.2, Some(SpecialMoveType::Promotion(_))) && !self.is_capture(m).unwrap())
     }
 
     /// Returns an optional boolean representing the side in stalemate (`None` if neither side is in stalemate).
@@ -400,6 +760,16 @@ impl Position {
         }
     }
 
+    /// Returns the square and piece giving checkmate, or `None` if the position is not checkmate. In a double check,
+    /// which of the checking pieces moved last cannot be known from the position, so the one on the lowest square index is returned.
+    pub fn mating_piece(&self) -> Option<(usize, Piece)> {
+        if !self.is_checkmate() {
+            return None;
+        }
+        let sq = self.attackers_of(self.king_square(self.side), !self.side)[0];
+        self.content[sq].map(|piece| (sq, piece))
+    }
+
     /// Generates the pseudolegal moves in the position.
     pub fn gen_pseudolegal_moves(&self) -> Vec<Move> {
         let mut pseudolegal_moves = Vec::new();
@@ -409,6 +779,26 @@ impl Position {
         pseudolegal_moves
     }
 
+    /// Counts the pseudolegal moves of the side `color`, grouped by the type of the moving piece, regardless of which side is to move.
+    /// The counts are pseudolegal, so moves that would leave the king in check are included; castling moves are counted as king moves.
+    /// Piece types with no pseudolegal moves are omitted.
+    pub fn mobility_by_piece(&self, color: Color) -> HashMap<PieceType, usize> {
+        let position = if color == self.side {
+            self.clone()
+        } else {
+            Self {
+                side: color,
+                ep_target: None,
+                ..self.clone()
+            }
+        };
+        let mut mobility = HashMap::new();
+        for Move(src, ..) in position.gen_pseudolegal_moves() {
+            *mobility.entry(position.content[src].unwrap().0).or_insert(0) += 1;
+        }
+        mobility
+    }
+
     /// Generates the pseudolegal moves **from** a specific square.
     /// The square index `i` can be converted from a square name using the [`sq_to_idx`](super::sq_to_idx) function.
     pub fn gen_pseudolegal_moves_sq(&self, i: usize) -> Vec<Move> {
@@ -442,26 +832,16 @@ impl Position {
                     let castling_rights_idx_offset = if side.is_white() { 0 } else { 2 };
                     let (oo_sq, ooo_sq) = if side.is_white() { (6, 2) } else { (62, 58) };
                     let (kingside, queenside) = (castling_rights[castling_rights_idx_offset], castling_rights[castling_rights_idx_offset + 1]);
-                    if let Some(r) = kingside {
-                        match helpers::count_pieces(i + 1..=oo_sq, content) {
-                            0 => pseudolegal_moves.push(Move(i, oo_sq, Some(SpecialMoveType::CastlingKingside))),
-                            1 => {
-                                if helpers::find_all_pieces(i + 1..=oo_sq, content)[0] == r {
-                                    pseudolegal_moves.push(Move(i, oo_sq, Some(SpecialMoveType::CastlingKingside)))
-                                }
-                            }
-                            _ => (),
-                        }
-                    }
-                    if let Some(r) = queenside {
-                        match helpers::count_pieces(ooo_sq..i, content) {
-                            0 => pseudolegal_moves.push(Move(i, ooo_sq, Some(SpecialMoveType::CastlingQueenside))),
-                            1 => {
-                                if helpers::find_all_pieces(ooo_sq..i, content)[0] == r {
-                                    pseudolegal_moves.push(Move(i, ooo_sq, Some(SpecialMoveType::CastlingQueenside)))
-                                }
+                    for (right, king_dest, rook_dest, spec) in [
+                        (kingside, oo_sq, oo_sq - 1, SpecialMoveType::CastlingKingside),
+                        (queenside, ooo_sq, ooo_sq + 1, SpecialMoveType::CastlingQueenside),
+                    ] {
+                        if let Some(r) = right {
+                            let squares = [i, king_dest, r, rook_dest];
+                            let (lo, hi) = (*squares.iter().min().unwrap(), *squares.iter().max().unwrap());
+                            if (lo..=hi).all(|sq| sq == i || sq == r || content[sq].is_none()) {
+                                pseudolegal_moves.push(Move(i, king_dest, Some(spec)));
                             }
-                            _ => (),
                         }
                     }
                     pseudolegal_moves
@@ -620,6 +1000,95 @@ impl Position {
         .any(|Move(_, dest, _)| dest == sq)
     }
 
+    /// Returns the indices of all the squares holding pieces of color `by` that attack the square `sq`, in ascending order.
+    /// Pawns attack diagonally forward, and sliding pieces are stopped by the first piece of either color in their way.
+    /// Whether capturing on `sq` would actually be legal (e.g. for a pinned piece) is not considered.
+    /// The square indices are the same as those from the [`sq_to_idx`](super::sq_to_idx) function.
+    pub fn attackers_of(&self, sq: usize, by: Color) -> Vec<usize> {
+        (0..64).filter(|&src| self.attacks_from(src, sq, by)).collect()
+    }
+
+    /// Checks whether any piece of color `by` attacks the square `sq`, as in [`Position::attackers_of`], stopping at the first attacker found.
+    pub fn is_attacked_by(&self, sq: usize, by: Color) -> bool {
+        (0..64).any(|src| self.attacks_from(src, sq, by))
+    }
+
+    /// Returns every piece of the side `color` that is absolutely pinned to its king, as pairs of the square of the pinned piece
+    /// and the square of the enemy bishop, rook or queen pinning it, ordered by the square of the pinned piece.
+    /// A piece is pinned if it is the only piece between its king and an enemy slider moving along that line.
+    pub fn pinned_pieces(&self, color: Color) -> Vec<(usize, usize)> {
+        let king = helpers::find_king(color, &self.content);
+        let mut pins: Vec<_> = (0..64)
+            .filter_map(|slider| {
+                let Some(Piece(piece_type @ (PieceType::B | PieceType::R | PieceType::Q), c)) = self.content[slider] else {
+                    return None;
+                };
+                let straight = king % 8 == slider % 8 || king / 8 == slider / 8;
+                if c == color || straight && piece_type == PieceType::B || !straight && piece_type == PieceType::R {
+                    return None;
+                }
+                match helpers::squares_between(king, slider)?.into_iter().filter(|&sq| self.content[sq].is_some()).collect::<Vec<_>>()[..] {
+                    [pinned] if matches!(self.content[pinned], Some(Piece(_, c)) if c == color) => Some((pinned, slider)),
+                    _ => None,
+                }
+            })
+            .collect();
+        pins.sort();
+        pins
+    }
+
+    /// Checks whether the piece on the square `sq` is absolutely pinned to its king (see [`Position::pinned_pieces`]),
+    /// returning `false` if the square is empty.
+    pub fn is_pinned(&self, sq: usize) -> bool {
+        match self.content[sq] {
+            Some(Piece(_, color)) => self.pinned_pieces(color).iter().any(|&(pinned, _)| pinned == sq),
+            None => false,
+        }
+    }
+
+    /// Checks whether a piece of color `by` on the square `src` attacks the square `sq`.
+    fn attacks_from(&self, src: usize, sq: usize, by: Color) -> bool {
+        matches!(self.content[src], Some(Piece(_, c)) if c == by) && helpers::attacks(&self.content, src, sq)
+    }
+
+    /// Checks whether a pawn of color `by` attacks the square `sq`, considering only the two squares diagonally behind `sq` from that side's perspective.
+    /// This is a faster alternative to a general attack test, for evaluation terms such as outposts and pawn storms.
+    /// The square index `sq` can be converted from a square name using the [`sq_to_idx`](super::sq_to_idx) function.
+    pub fn is_attacked_by_pawn(&self, sq: usize, by: Color) -> bool {
+        let (toward_a, toward_h) = if by.is_white() { (-9, -7) } else { (7, 9) };
+        [(toward_a, !sq.is_multiple_of(8)), (toward_h, sq % 8 != 7)].into_iter().any(|(offset, on_board)| {
+            let src = sq as isize + offset;
+            on_board && (0..64).contains(&src) && self.content[src as usize] == Some(Piece(PieceType::P, by))
+        })
+    }
+
+    /// Returns the net control of every square, i.e. the number of white pieces attacking it minus the number of black pieces attacking it.
+    /// The map is indexed by rank and then file, so `control()[0][0]` is the control of a1, `control()[0][7]` of h1 and `control()[7][7]` of h8.
+    /// Attacks are counted regardless of pins and of whether the square is occupied, and this function is fairly expensive.
+    pub fn control(&self) -> [[i8; 8]; 8] {
+        let mut control = [[0; 8]; 8];
+        for (sq, net) in control.iter_mut().flatten().enumerate() {
+            *net = self.attackers_of(sq, Color::White).len() as i8 - self.attackers_of(sq, Color::Black).len() as i8;
+        }
+        control
+    }
+
+    /// Returns the square index and the piece of the least valuable piece of color `by` that attacks the square `sq`
+    /// (`None` if no piece of that color attacks the square). Pieces are ranked from least to most valuable as pawn, knight,
+    /// bishop, rook, queen and king, and ties are broken by the lowest square index.
+    /// The square index `sq` can be converted from a square name using the [`sq_to_idx`](super::sq_to_idx) function.
+    pub fn least_valuable_attacker(&self, sq: usize, by: Color) -> Option<(usize, Piece)> {
+        let rank = |piece_type| {
+            [PieceType::P, PieceType::N, PieceType::B, PieceType::R, PieceType::Q, PieceType::K]
+                .iter()
+                .position(|&pt| pt == piece_type)
+        };
+        self.attackers_of(sq, by)
+            .into_iter()
+            .map(|src| (src, self.content[src].unwrap()))
+            .min_by_key(|(_, Piece(piece_type, _))| rank(*piece_type))
+    }
+
     /// Counts the material on the board. This function is used by [`Position::is_insufficient_material`] to determine whether there is insufficient checkmating material.
     pub(crate) fn count_material(&self) -> Vec<Material> {
         let mut material = Vec::new();
@@ -668,18 +1137,313 @@ impl Position {
         false
     }
 
+    /// Checks whether the side `color` has enough material to force checkmate against a lone king, regardless of the opponent's material.
+    /// This is the case with a queen, a rook or a pawn (which can promote), bishops on both square colors, a bishop and a knight,
+    /// or three knights, but not with a lone king, knights alone (as two knights cannot force mate), or bishops all on one square color.
+    pub fn can_checkmate_with_current_material(&self, color: Color) -> bool {
+        let MaterialCount {
+            pawns,
+            knights,
+            bishops,
+            rooks,
+            queens,
+        } = self.material_count(color);
+        let bishops_on_both_colors = self.bishops_on_color(color, Color::White) > 0 && self.bishops_on_color(color, Color::Black) > 0;
+        queens + rooks + pawns > 0 || bishops_on_both_colors || bishops > 0 && knights > 0 || knights >= 3
+    }
+
+    /// Checks whether the side `color` could checkmate the opponent by some series of legal moves, however unlikely, which decides
+    /// whether a loss on time by the opponent is a win or a draw. A lone king cannot, and neither can a lone knight or bishops on a single
+    /// square color unless the opponent has a piece that could block its own king in (other than bishops on that same square color).
+    pub(crate) fn could_checkmate(&self, color: Color) -> bool {
+        let pieces = |c| self.pieces_of(c).filter(|(_, Piece(pt, _))| *pt != PieceType::K).map(|(sq, Piece(pt, _))| (sq, pt)).collect::<Vec<_>>();
+        let (ours, theirs) = (pieces(color), pieces(!color));
+        let bishop_complexes: Vec<_> = ours.iter().filter(|(_, pt)| *pt == PieceType::B).map(|&(sq, _)| helpers::color_complex_of(sq)).collect();
+        if ours.iter().any(|(_, pt)| matches!(pt, PieceType::Q | PieceType::R | PieceType::P)) {
+            return true;
+        }
+        match (ours.len() - bishop_complexes.len(), bishop_complexes.as_slice()) {
+            (0, []) => false,
+            (1, []) => !theirs.is_empty(),
+            (_, []) | (1.., _) => true,
+            (0, [first, rest @ ..]) if rest.iter().any(|c| c != first) => true,
+            (0, [first, ..]) => theirs.iter().any(|&(sq, pt)| pt != PieceType::B || helpers::color_complex_of(sq) != *first),
+        }
+    }
+
+    /// Checks whether neither side can force checkmate against best defence. See [`Board::cannot_force_mate`](super::Board::cannot_force_mate) for the scenarios covered.
+    pub fn cannot_force_mate(&self) -> bool {
+        if self.is_insufficient_material() {
+            return true;
+        }
+        let pieces_of = |color| {
+            let mut pieces: Vec<_> = self.content.iter().filter_map(|&o| o.filter(|p| p.1 == color && p.0 != PieceType::K).map(|p| p.0)).collect();
+            pieces.sort_by_key(|&pt| char::from(pt));
+            pieces
+        };
+        let (white, black) = (pieces_of(Color::White), pieces_of(Color::Black));
+        let is_minor = |pieces: &[PieceType]| matches!(pieces, [] | [PieceType::N] | [PieceType::B]);
+        let is_two_knights = |pieces: &[PieceType]| pieces == [PieceType::N, PieceType::N];
+        is_minor(&white) && is_minor(&black) || is_two_knights(&white) && black.is_empty() || is_two_knights(&black) && white.is_empty()
+    }
+
+    /// Counts the pawns of the side `color` that stand on the pawn shield squares in front of its king.
+    /// The shield squares are the three squares on the rank directly in front of the king (from that side's perspective),
+    /// on the f-, g- and h-files if the king is on one of those files, on the a-, b- and c-files if the king is on one of
+    /// those files, and on the king's file and the two adjacent files otherwise. If the king is on the last rank, there
+    /// are no shield squares and the count is zero.
+    pub fn pawn_shield_count(&self, color: Color) -> usize {
+        let king = helpers::find_king(color, &self.content);
+        let (king_file, king_rank) = (king % 8, king / 8);
+        let rank = match (color, king_rank) {
+            (Color::White, 7) | (Color::Black, 0) => return 0,
+            (Color::White, r) => r + 1,
+            (Color::Black, r) => r - 1,
+        };
+        let files = match king_file {
+            5..=7 => 5..=7,
+            0..=2 => 0..=2,
+            f => f - 1..=f + 1,
+        };
+        files.filter(|file| self.content[rank * 8 + file] == Some(Piece(PieceType::P, color))).count()
+    }
+
+    /// Returns the material balance of the position, i.e. the total [value](PieceType::value) of white's pieces minus that of black's pieces,
+    /// which is positive when white is ahead. Kings are excluded, since they have no material value.
+    pub fn material_balance(&self) -> i32 {
+        self.content
+            .iter()
+            .flatten()
+            .map(|Piece(piece_type, color)| if color.is_white() { piece_type.value() } else { -piece_type.value() })
+            .sum()
+    }
+
+    /// Computes a 64-bit Zobrist hash of the position from its piece placement, side to move, castling rights, and en passant
+    /// target square file. As in the Polyglot convention, the en passant file is only hashed if a pawn of the side to move stands
+    /// beside the pawn that just double-pushed. The hash is the XOR of the keys of those features, which are stable within a major release,
+    /// so hashes may be persisted. The 781 keys are the outputs of SplitMix64 seeded with `0x7273636865737321`, in the following order:
+    /// - a key for each piece on each square, at index `64 * piece + square`, where `piece` is 0 to 5 for a white pawn, knight,
+    ///   bishop, rook, queen and king and 6 to 11 for the black pieces in the same order
+    /// - the key for black to move
+    /// - a key for each castling right, in the order [K, Q, k, q]
+    /// - a key for each en passant file, from the a file to the h file
+    pub fn zobrist_hash(&self) -> u64 {
+        zobrist::hash(self)
+    }
+
+    /// Counts the pieces of the side `color` by type, excluding the king, so that custom piece values can be applied to the counts.
+    pub fn material_count(&self, color: Color) -> MaterialCount {
+        let mut counts = MaterialCount::default();
+        for Piece(piece_type, _) in self.content.iter().flatten().filter(|p| p.1 == color) {
+            match piece_type {
+                PieceType::P => counts.pawns += 1,
+                PieceType::N => counts.knights += 1,
+                PieceType::B => counts.bishops += 1,
+                PieceType::R => counts.rooks += 1,
+                PieceType::Q => counts.queens += 1,
+                PieceType::K => (),
+            }
+        }
+        counts
+    }
+
+    /// Counts the rooks of the side `color` on the opponent's second rank (the 7th rank for white, and the 2nd rank for black).
+    pub fn rooks_on_seventh(&self, color: Color) -> usize {
+        let rank = if color.is_white() { 48..56 } else { 8..16 };
+        helpers::count_piece(rank, Piece(PieceType::R, color), &self.content)
+    }
+
+    /// Checks whether two rooks of the side `color` are connected, i.e. share a rank or file with no pieces between them.
+    pub fn connected_rooks(&self, color: Color) -> bool {
+        let rooks = helpers::find_pieces(Piece(PieceType::R, color), 0..64, &self.content);
+        rooks.iter().enumerate().any(|(i, &a)| {
+            rooks[i + 1..]
+                .iter()
+                .any(|&b| (a % 8 == b % 8 || a / 8 == b / 8) && helpers::squares_between(a, b).unwrap_or_default().iter().all(|&sq| self.content[sq].is_none()))
+        })
+    }
+
+    /// Counts the bishops of the side `color` on squares of the color `square_color` (`Color::White` for light squares and `Color::Black` for dark squares).
+    pub fn bishops_on_color(&self, color: Color, square_color: Color) -> usize {
+        helpers::find_pieces(Piece(PieceType::B, color), 0..64, &self.content)
+            .into_iter()
+            .filter(|&sq| helpers::color_complex_of(sq) == square_color.is_white())
+            .count()
+    }
+
+    /// Checks whether the position is closed, using a heuristic based on the pawn structure: a position is considered closed
+    /// if there are at least three locked pawn pairs, i.e. white pawns directly blocked by a black pawn on the square in front of them.
+    /// Closed positions typically favor knights over bishops.
+    pub fn is_closed(&self) -> bool {
+        (8..56)
+            .filter(|&sq| self.content[sq] == Some(Piece(PieceType::P, Color::White)) && self.content[sq + 8] == Some(Piece(PieceType::P, Color::Black)))
+            .count()
+            >= 3
+    }
+
     /// Returns which side's turn it is to move.
     pub fn side_to_move(&self) -> Color {
         self.side
     }
 
+    /// Returns the castling rights of both sides in the position.
+    pub fn castling_rights(&self) -> CastlingRights {
+        CastlingRights(self.castling_rights)
+    }
+
+    /// Returns the index of the en passant target square, if there is one.
+    pub fn en_passant_square(&self) -> Option<usize> {
+        self.ep_target
+    }
+
+    /// Returns an iterator over the occupied squares and their pieces, in increasing order of square index.
+    pub fn pieces(&self) -> impl Iterator<Item = (usize, Piece)> + '_ {
+        self.content.iter().enumerate().filter_map(|(sq, piece)| piece.map(|piece| (sq, piece)))
+    }
+
+    /// Returns an iterator over the squares occupied by the pieces of the side `color` and their pieces, in increasing order of square index.
+    pub fn pieces_of(&self, color: Color) -> impl Iterator<Item = (usize, Piece)> + '_ {
+        self.pieces().filter(move |(_, Piece(_, c))| *c == color)
+    }
+
+    /// Returns the index of the square of the king of the side `color`.
+    pub fn king_square(&self, color: Color) -> usize {
+        helpers::find_king(color, &self.content)
+    }
+
+    /// Checks the position for every problem that would make it illegal, returning an empty vector if the position is legal.
+    ///
+    /// Unlike [`Fen::try_from`](super::Fen), which stops at the first invalid aspect, this function reports all problems in one pass,
+    /// which is useful for position editors that want to show every error at once.
+    pub fn validate(&self) -> Vec<PositionProblem> {
+        let mut problems = Vec::new();
+        let count = |piece_type, color| helpers::count_piece(0..64, Piece(piece_type, color), &self.content);
+        for color in [Color::White, Color::Black] {
+            match count(PieceType::K, color) {
+                0 => problems.push(PositionProblem::MissingKing(color)),
+                1 => (),
+                _ => problems.push(PositionProblem::TooManyKings(color)),
+            }
+        }
+        for sq in (0..8).chain(56..64) {
+            if let Some(Piece(PieceType::P, _)) = self.content[sq] {
+                problems.push(PositionProblem::PawnOnBackRank(sq));
+            }
+        }
+        for color in [Color::White, Color::Black] {
+            let pawns = count(PieceType::P, color);
+            if pawns > 8 {
+                problems.push(PositionProblem::TooManyPawns(color));
+            }
+            if self.content.iter().filter(|o| matches!(o, Some(Piece(_, c)) if *c == color)).count() > 16 {
+                problems.push(PositionProblem::TooManyPieces(color));
+            }
+            let promoted = count(PieceType::Q, color).saturating_sub(1)
+                + count(PieceType::R, color).saturating_sub(2)
+                + count(PieceType::B, color).saturating_sub(2)
+                + count(PieceType::N, color).saturating_sub(2);
+            if promoted > 8usize.saturating_sub(pawns) {
+                problems.push(PositionProblem::ImpossiblePromotions(color));
+            }
+        }
+        if let (Some(sq), false) = (self.ep_target, self.validate_en_passant()) {
+            problems.push(PositionProblem::InvalidEnPassantTarget(sq));
+        }
+        problems.extend(self.validate_castling_rights().into_iter().map(PositionProblem::InvalidCastlingRight));
+        // pawns on the back ranks would generate moves off the board, so the check is only looked for without them
+        let back_rank_pawns = problems.iter().any(|p| matches!(p, PositionProblem::PawnOnBackRank(_)));
+        if !back_rank_pawns && [Color::White, Color::Black].iter().all(|&color| count(PieceType::K, color) == 1) && helpers::king_capture_pseudolegal(&self.content, self.side) {
+            problems.push(PositionProblem::OpponentInCheck);
+        }
+        problems
+    }
+
+    /// Returns the rook squares of the castling rights that could not be exercised as recorded, i.e. those whose square
+    /// does not hold a rook of the right color on its back rank, or whose rook is not on the correct side of a king on the same rank.
+    pub fn validate_castling_rights(&self) -> Vec<usize> {
+        let mut invalid = Vec::new();
+        for (i, right) in self.castling_rights.iter().enumerate() {
+            let Some(sq) = *right else {
+                continue;
+            };
+            let color = if i < 2 { Color::White } else { Color::Black };
+            let back_rank = if color.is_white() { 0..8 } else { 56..64 };
+            let king = back_rank.clone().find(|&s| self.content[s] == Some(Piece(PieceType::K, color)));
+            let valid = back_rank.contains(&sq) && self.content[sq] == Some(Piece(PieceType::R, color)) && king.is_some_and(|king| if i % 2 == 0 { sq > king } else { sq < king });
+            if !valid {
+                invalid.push(sq);
+            }
+        }
+        invalid
+    }
+
+    /// Checks whether the en passant target square (if any) could have been created by the opponent's last move, i.e.
+    /// it is on the 6th rank with white to move or the 3rd rank with black to move, an enemy pawn stands directly in
+    /// front of it, and both it and the square the pawn double-pushed from are empty. Returns `true` if there is no en passant target square.
+    pub fn validate_en_passant(&self) -> bool {
+        let Some(sq) = self.ep_target else {
+            return true;
+        };
+        let (ranks, pawn_sq, origin_sq) = if self.side.is_white() {
+            (40..48, sq.wrapping_sub(8), sq + 8)
+        } else {
+            (16..24, sq + 8, sq.wrapping_sub(8))
+        };
+        ranks.contains(&sq) && self.content[pawn_sq] == Some(Piece(PieceType::P, !self.side)) && self.content[sq].is_none() && self.content[origin_sq].is_none()
+    }
+
+    /// Returns a copy of the position with the en passant target square removed if no legal en passant capture is possible.
+    pub(crate) fn with_normalized_ep(&self) -> Self {
+        let mut position = self.clone();
+        if !self.gen_non_illegal_moves().iter().any(|m| m.2 == Some(SpecialMoveType::EnPassant)) {
+            position.ep_target = None;
+        }
+        position
+    }
+
     /// Checks whether the given move is a capture, returning an error if it is illegal in this position.
     pub fn is_capture(&self, move_: Move) -> Result<bool, IllegalMoveError> {
         let move_ = match helpers::as_legal(move_, &self.gen_non_illegal_moves()) {
             Some(m) => m,
             _ => return Err(IllegalMoveError(move_)),
         };
-        Ok(move_.2 == Some(SpecialMoveType::EnPassant) || self.content[move_.1].is_some())
+        let castling = matches!(move_.2, Some(SpecialMoveType::CastlingKingside | SpecialMoveType::CastlingQueenside));
+        Ok(move_.2 == Some(SpecialMoveType::EnPassant) || self.content[move_.1].is_some() && !castling)
+    }
+
+    /// Checks whether the given move is a legal castling move in this position, which moves both the king and a rook.
+    /// A move with [`SpecialMoveType::Unclear`] from the king to its castling destination, or (as in Chess960 UCI) onto its castling rook, counts.
+    pub fn is_castling(&self, move_: Move) -> bool {
+        matches!(
+            helpers::as_legal(self.resolve_king_onto_rook(move_), &self.gen_non_illegal_moves()),
+            Some(Move(_, _, Some(SpecialMoveType::CastlingKingside | SpecialMoveType::CastlingQueenside)))
+        )
+    }
+
+    /// Checks whether the given move is a legal en passant capture in this position, which removes a pawn from a square other than its destination.
+    pub fn is_en_passant(&self, move_: Move) -> bool {
+        matches!(helpers::as_legal(move_, &self.gen_non_illegal_moves()), Some(Move(_, _, Some(SpecialMoveType::EnPassant))))
+    }
+
+    /// Checks whether making the given move would put the opponent in check, returning `false` if the move is illegal in this position.
+    /// Discovered checks are included, such as those from an en passant capture that uncovers a slider, as are checks by a promoted piece.
+    /// Only the board content is updated to test for check, rather than the whole position.
+    pub fn move_gives_check(&self, move_: Move) -> bool {
+        let Some(move_) = helpers::as_legal(move_, &self.gen_non_illegal_moves()) else {
+            return false;
+        };
+        helpers::king_capture_pseudolegal(&helpers::change_content(&self.content, &move_, &self.castling_rights), self.side)
+    }
+
+    /// Checks whether the given move puts the opponent in check (see [`Position::move_gives_check`]), returning an error if it is illegal in this position.
+    pub fn gives_check(&self, move_: Move) -> Result<bool, IllegalMoveError> {
+        let move_ = helpers::as_legal(move_, &self.gen_non_illegal_moves()).ok_or(IllegalMoveError(move_))?;
+        Ok(self.move_gives_check(move_))
+    }
+
+    /// Returns every legal move that puts the opponent in check (an empty vector if there are none), in move generation order.
+    pub fn checking_moves(&self) -> Vec<Move> {
+        self.gen_non_illegal_moves().into_iter().filter(|&m| self.move_gives_check(m)).collect()
     }
 }
 
@@ -690,6 +1454,149 @@ impl fmt::Display for Position {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Position {
+    /// Serializes the position as the first four fields of an FEN (see [`Position::to_fen`]).
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_fen())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Position {
+    /// Deserializes a position from the first four fields of an FEN, returning an error if they are invalid.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = String::deserialize(deserializer)?;
+        let fen = super::Fen::try_from(format!("{fields} 0 1").as_str()).map_err(serde::de::Error::custom)?;
+        Ok(fen.position)
+    }
+}
+
+/// The information needed to take back a move made with [`Position::make_move_in_place`].
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct Undo {
+    /// The squares changed by the move, with their previous occupants
+    squares: [(usize, Option<Piece>); 4],
+    /// The castling rights before the move
+    castling_rights: [Option<usize>; 4],
+    /// The en passant target square before the move
+    ep_target: Option<usize>,
+}
+
+/// Represents the options for pretty-printing a position.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct PrintOptions {
+    /// Whether to use piece characters like 'K' and 'p' instead of characters like '♔' and '♟'
+    pub ascii: bool,
+    /// Whether to label the ranks and files of the board
+    pub coordinates: bool,
+}
+
+impl Default for PrintOptions {
+    /// The default `PrintOptions` use Unicode piece characters and include coordinates.
+    fn default() -> Self {
+        Self { ascii: false, coordinates: true }
+    }
+}
+
+/// Represents the castling rights of both sides.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct CastlingRights(pub(crate) [Option<usize>; 4]);
+
+impl CastlingRights {
+    /// Checks whether the side `color` has the right to castle kingside.
+    pub fn kingside(&self, color: Color) -> bool {
+        self.0[if color.is_white() { 0 } else { 2 }].is_some()
+    }
+
+    /// Checks whether the side `color` has the right to castle queenside.
+    pub fn queenside(&self, color: Color) -> bool {
+        self.0[if color.is_white() { 1 } else { 3 }].is_some()
+    }
+
+    /// Checks whether the side `color` has the right to castle on either side.
+    pub fn any(&self, color: Color) -> bool {
+        self.kingside(color) || self.queenside(color)
+    }
+}
+
+/// Represents the number of pieces of each type (other than the king) that a side has, as returned by [`Position::material_count`].
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Default)]
+pub struct MaterialCount {
+    pub pawns: usize,
+    pub knights: usize,
+    pub bishops: usize,
+    pub rooks: usize,
+    pub queens: usize,
+}
+
+impl MaterialCount {
+    /// Returns the number of pieces of the given type (always 0 for the king, which is not counted).
+    pub fn count(&self, piece_type: PieceType) -> usize {
+        match piece_type {
+            PieceType::P => self.pawns,
+            PieceType::N => self.knights,
+            PieceType::B => self.bishops,
+            PieceType::R => self.rooks,
+            PieceType::Q => self.queens,
+            PieceType::K => 0,
+        }
+    }
+}
+
+/// Represents a problem that makes a position illegal, as reported by [`Position::validate`].
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub enum PositionProblem {
+    /// The side has no king.
+    MissingKing(Color),
+    /// The side has more than one king.
+    TooManyKings(Color),
+    /// There is a pawn on the 1st or 8th rank, with the tuple value being the square index of the pawn.
+    PawnOnBackRank(usize),
+    /// The side not to move is in check.
+    OpponentInCheck,
+    /// The side has more than eight pawns.
+    TooManyPawns(Color),
+    /// The side has more than sixteen pieces.
+    TooManyPieces(Color),
+    /// The side has more promoted pieces than it could have obtained from its missing pawns.
+    ImpossiblePromotions(Color),
+    /// The en passant target square could not have been created by a pawn double push (see [`Position::validate_en_passant`]), with the tuple value being its square index.
+    InvalidEnPassantTarget(usize),
+    /// A castling right refers to a square that does not hold one of the side's rooks on its back rank, on the correct side of its king
+    /// (see [`Position::validate_castling_rights`]), with the tuple value being the square index.
+    InvalidCastlingRight(usize),
+}
+
+impl fmt::Display for PositionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |c: &Color| if c.is_white() { "white" } else { "black" };
+        match self {
+            Self::MissingKing(c) => write!(f, "{} does not have a king", name(c)),
+            Self::TooManyKings(c) => write!(f, "{} cannot have more than one king", name(c)),
+            Self::PawnOnBackRank(sq) => {
+                let (file, rank) = helpers::idx_to_sq(*sq);
+                write!(f, "there cannot be a pawn on {file}{rank}, pawns cannot be on the 1st and 8th ranks")
+            }
+            Self::OpponentInCheck => write!(f, "when one side is in check, it cannot be the other side's turn to move"),
+            Self::TooManyPawns(c) => write!(f, "{} cannot have more than eight pawns", name(c)),
+            Self::TooManyPieces(c) => write!(f, "{} cannot have more than sixteen pieces", name(c)),
+            Self::ImpossiblePromotions(c) => write!(f, "{} has more promoted pieces than missing pawns", name(c)),
+            Self::InvalidEnPassantTarget(sq) => {
+                let (file, rank) = helpers::idx_to_sq(*sq);
+                write!(f, "{file}{rank} cannot be the en passant target square, it must be behind an enemy pawn that just double-pushed")
+            }
+            Self::InvalidCastlingRight(sq) => {
+                let (file, rank) = helpers::idx_to_sq(*sq);
+                write!(
+                    f,
+                    "there cannot be a castling right with the rook on {file}{rank}, it must hold a rook on the same back rank as its king"
+                )
+            }
+        }
+    }
+}
+
 /// Represents a piece of material.
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Material {
@@ -0,0 +1,75 @@
+use super::{Color, InvalidPositionError, Piece, Position};
+
+/// A builder for setting up arbitrary positions piece by piece, without going through FEN.
+///
+/// The built position is checked with [`Position::validate`], so the castling rights and en passant target square
+/// must be consistent with the pieces on the board.
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct PositionBuilder {
+    content: [Option<Piece>; 64],
+    side: Color,
+    castling_rights: [Option<usize>; 4],
+    ep_target: Option<usize>,
+}
+
+impl PositionBuilder {
+    /// Creates a builder with an empty board and white to move.
+    pub fn new() -> Self {
+        Self {
+            content: [None; 64],
+            side: Color::White,
+            castling_rights: [None; 4],
+            ep_target: None,
+        }
+    }
+
+    /// Places `piece` on the square with index `sq`, or empties the square if `piece` is `None`.
+    /// Panics if `sq` is not in the range `0..64`.
+    pub fn set_piece(&mut self, sq: usize, piece: Option<Piece>) -> &mut Self {
+        self.content[sq] = piece;
+        self
+    }
+
+    /// Sets the side to move.
+    pub fn set_side_to_move(&mut self, side: Color) -> &mut Self {
+        self.side = side;
+        self
+    }
+
+    /// Sets the castling rights as the square indices of the castling rooks, in the order [K, Q, k, q], with `None` for a missing right.
+    /// Squares other than the corner ones may be given for Chess960 positions; each rook must be on its side's back rank, on the correct
+    /// side of its king, which is checked by [`PositionBuilder::build`].
+    pub fn set_castling_rights(&mut self, castling_rights: [Option<usize>; 4]) -> &mut Self {
+        self.castling_rights = castling_rights;
+        self
+    }
+
+    /// Sets the index of the en passant target square, or removes it if `ep_target` is `None`.
+    /// The square must be on the 6th rank with white to move or the 3rd rank with black to move; this is checked by [`PositionBuilder::build`].
+    pub fn set_en_passant(&mut self, ep_target: Option<usize>) -> &mut Self {
+        self.ep_target = ep_target;
+        self
+    }
+
+    /// Builds the position, returning an error containing every problem found by [`Position::validate`] if it is illegal.
+    pub fn build(&self) -> Result<Position, InvalidPositionError> {
+        let position = Position {
+            content: self.content,
+            side: self.side,
+            castling_rights: self.castling_rights,
+            ep_target: self.ep_target,
+        };
+        let problems = position.validate();
+        if problems.is_empty() {
+            Ok(position)
+        } else {
+            Err(InvalidPositionError(problems))
+        }
+    }
+}
+
+impl Default for PositionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
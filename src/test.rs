@@ -1,4 +1,4 @@
-use super::{helpers, Board, Color, Fen, Move, PieceType, SpecialMoveType};
+use super::{helpers, Board, Color, Fen, Move, Piece, PieceType, SpecialMoveType};
 
 #[test]
 fn default_board() {
@@ -182,16 +182,16 @@ fn legal_moves() {
     ];
     check(board, &legal);
     let board = Board::from_fen(Fen::try_from("1k6/3p4/1K6/2P5/8/8/8/8 b - - 0 1").unwrap());
-    let legal = [Move(51, 43, None), Move(51, 35, None), Move(57, 58, None), Move(57, 56, None)];
+    let legal = [Move(51, 35, None), Move(51, 43, None), Move(57, 56, None), Move(57, 58, None)];
     check(board, &legal);
     let board = Board::from_fen(Fen::try_from("1k6/8/1K6/2Pp4/8/8/8/8 w - d6 0 2").unwrap());
     let legal = [
         Move(34, 42, None),
         Move(34, 43, Some(SpecialMoveType::EnPassant)),
-        Move(41, 42, None),
-        Move(41, 40, None),
-        Move(41, 33, None),
         Move(41, 32, None),
+        Move(41, 33, None),
+        Move(41, 40, None),
+        Move(41, 42, None),
     ];
     check(board, &legal);
     let board = Board::from_fen(Fen::try_from("k7/3N4/K7/8/8/8/8/8 w - - 0 1").unwrap());
@@ -199,9 +199,9 @@ fn legal_moves() {
     check(board, &legal);
     let board = Board::from_fen(Fen::try_from("k7/3P4/K7/8/8/8/8/8 w - - 0 1").unwrap());
     let legal = [
-        Move(40, 41, None),
         Move(40, 32, None),
         Move(40, 33, None),
+        Move(40, 41, None),
         Move(51, 59, Some(SpecialMoveType::Promotion(PieceType::Q))),
         Move(51, 59, Some(SpecialMoveType::Promotion(PieceType::R))),
         Move(51, 59, Some(SpecialMoveType::Promotion(PieceType::B))),
@@ -214,15 +214,15 @@ fn legal_moves() {
         Move(15, 7, Some(SpecialMoveType::Promotion(PieceType::R))),
         Move(15, 7, Some(SpecialMoveType::Promotion(PieceType::B))),
         Move(15, 7, Some(SpecialMoveType::Promotion(PieceType::N))),
-        Move(40, 41, None),
         Move(40, 32, None),
         Move(40, 33, None),
+        Move(40, 41, None),
     ];
     check(board, &legal);
     let board = Board::from_fen(Fen::try_from("8/8/8/8/8/4k3/4p3/4K2R w K - 0 1").unwrap());
     let legal = [
-        Move(7, 6, None),
         Move(7, 5, None),
+        Move(7, 6, None),
         Move(7, 15, None),
         Move(7, 23, None),
         Move(7, 31, None),
@@ -236,8 +236,8 @@ fn legal_moves() {
     let legal = [
         Move(4, 5, None),
         Move(4, 6, Some(SpecialMoveType::CastlingKingside)),
-        Move(7, 6, None),
         Move(7, 5, None),
+        Move(7, 6, None),
         Move(7, 15, None),
         Move(7, 23, None),
         Move(7, 31, None),
@@ -249,6 +249,21 @@ fn legal_moves() {
     check(board, &legal);
 }
 
+#[test]
+fn legal_move_order() {
+    let mut board = Board::default();
+    board.make_moves_san("e4 d5 exd5 Nf6").unwrap();
+    let moves = board.gen_legal_moves();
+    let mut sorted = moves.clone();
+    sorted.sort_by_key(|m| (m.0, m.1));
+    assert_eq!(moves, sorted);
+    let uci: Vec<_> = Board::default().gen_legal_moves().iter().map(|m| m.to_uci()).collect();
+    assert_eq!(
+        uci,
+        ["b1a3", "b1c3", "g1f3", "g1h3", "a2a3", "a2a4", "b2b3", "b2b4", "c2c3", "c2c4", "d2d3", "d2d4", "e2e3", "e2e4", "f2f3", "f2f4", "g2g3", "g2g4", "h2h3", "h2h4"]
+    );
+}
+
 #[test]
 fn undo_move() {
     let mut board = Board::default();
@@ -261,10 +276,10 @@ fn undo_move() {
     assert_eq!(board.fullmove_number(), 4);
     assert_eq!(board.halfmove_clock(), 4);
     println!("{board}");
-    board.undo_move().unwrap();
+    assert_eq!(board.undo_move().unwrap(), Move(62, 45, None));
     assert_eq!(board.fullmove_number(), 3);
     assert_eq!(board.halfmove_clock(), 3);
-    board.undo_move().unwrap();
+    assert_eq!(board.undo_move().unwrap(), Move(5, 26, None));
     assert_eq!(board.fullmove_number(), 3);
     assert_eq!(board.halfmove_clock(), 2);
     board.make_moves_san("d4 exd4 Bc4 Nf6 O-O").unwrap();
@@ -277,6 +292,7 @@ fn undo_move() {
     assert_eq!(board.fullmove_number(), start_fn);
     assert_eq!(board.halfmove_clock(), start_hc);
     println!("{board}");
+    assert!(board.undo_move().is_err());
 }
 
 #[test]
@@ -401,3 +417,1809 @@ fn custom_piece_set() {
     pip.piece_set = img::PieceSet::Custom(hm);
     img::position_to_image(board.position(), pip, Color::White).unwrap().save("test1.png").unwrap();
 }
+
+#[test]
+fn resolve_captures() {
+    let board = Board::from_fen(Fen::try_from("4k3/2n5/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap());
+    let first_capture = |b: &Board| b.gen_legal_moves().into_iter().find(|&m| b.is_capture(m).unwrap()).unwrap();
+    let quiet = board.resolve_captures(first_capture);
+    assert_eq!(quiet.to_fen().to_string(), "4k3/8/8/3n4/8/8/8/4K3 w - - 0 2");
+    assert_eq!(board.resolve_captures(|_| Move::from_uci("e1e2").unwrap()), board);
+}
+
+#[test]
+fn fen_semantic_equality() {
+    let with_ep = Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+    let without_ep = Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+    assert_ne!(with_ep, without_ep);
+    assert!(with_ep.semantically_eq(&without_ep));
+    let capturable = Fen::try_from("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+    let not_capturable = Fen::try_from("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+    assert!(!capturable.semantically_eq(&not_capturable));
+}
+
+#[test]
+fn validate_position() {
+    use super::PositionProblem;
+
+    assert!(Board::default().position().validate().is_empty());
+    let mut position = Board::default().position().clone();
+    position.content[4] = None;
+    position.content[0] = Some(Piece(PieceType::K, Color::Black));
+    position.content[56] = Some(Piece(PieceType::P, Color::White));
+    position.content[20] = Some(Piece(PieceType::Q, Color::White));
+    position.content[19] = Some(Piece(PieceType::N, Color::White));
+    assert_eq!(
+        position.validate(),
+        [
+            PositionProblem::MissingKing(Color::White),
+            PositionProblem::TooManyKings(Color::Black),
+            PositionProblem::PawnOnBackRank(56),
+            PositionProblem::TooManyPawns(Color::White),
+            PositionProblem::TooManyPieces(Color::White),
+            PositionProblem::ImpossiblePromotions(Color::White),
+            PositionProblem::InvalidCastlingRight(7),
+            PositionProblem::InvalidCastlingRight(0),
+            PositionProblem::InvalidCastlingRight(56),
+        ]
+    );
+    let mut position = Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap().position().clone();
+    position.content[60] = None;
+    position.content[56] = Some(Piece(PieceType::K, Color::Black));
+    assert_eq!(position.validate(), [PositionProblem::OpponentInCheck]);
+}
+
+#[test]
+fn color_flipped_board() {
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5 Nf3 Nc6 Bb5 a6 O-O").unwrap();
+    board.resign(Color::Black).unwrap();
+    let flipped = board.color_flipped();
+    assert_eq!(flipped.gen_movetext(), "1... e5 2. e4 Nf6 3. Nc3 Bb4 4. a3 O-O");
+    assert_eq!(flipped.side_to_move(), Color::White);
+    assert_eq!(flipped.resigned_side(), Some(Color::White));
+    assert_eq!(flipped.color_flipped().position(), board.position());
+}
+
+#[test]
+fn castling_rights_history() {
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5 Ke2 Nc6 Ke1").unwrap();
+    let history = board.castling_rights_history();
+    assert_eq!(history.len(), 6);
+    assert!(history[2].kingside(Color::White) && history[2].queenside(Color::White));
+    assert!(!history[3].any(Color::White));
+    assert!(history[5].kingside(Color::Black) && !history[5].any(Color::White));
+}
+
+#[test]
+fn training_samples() {
+    let mut board = Board::default();
+    board.make_moves_san("f3 e5 g4").unwrap();
+    assert!(board.training_samples().iter().all(|(_, winner)| winner.is_none()));
+    board.make_move_san("Qh4#").unwrap();
+    let samples = board.training_samples();
+    assert_eq!(samples.len(), 5);
+    assert_eq!(samples[0].0, *Board::default().position());
+    assert_eq!(samples[4].0, *board.position());
+    assert!(samples.iter().all(|(_, winner)| *winner == Some(Color::Black)));
+    let mut board = Board::default();
+    board.make_move_san("e4").unwrap();
+    board.agree_draw().unwrap();
+    let samples = board.training_samples();
+    assert_eq!(samples.len(), 2);
+    assert!(samples.iter().all(|(_, winner)| winner.is_none()));
+}
+
+#[test]
+fn least_valuable_attacker() {
+    let position = Fen::try_from("4k3/8/3q4/2n5/4p3/5B2/4R3/3QK3 w - - 0 1").unwrap().position().clone();
+    assert_eq!(
+        position.least_valuable_attacker(helpers::sq_to_idx('e', '4'), Color::White),
+        Some((21, Piece(PieceType::B, Color::White)))
+    );
+    assert_eq!(
+        position.least_valuable_attacker(helpers::sq_to_idx('d', '3'), Color::Black),
+        Some((28, Piece(PieceType::P, Color::Black)))
+    );
+    assert_eq!(
+        position.least_valuable_attacker(helpers::sq_to_idx('d', '6'), Color::White),
+        Some((3, Piece(PieceType::Q, Color::White)))
+    );
+    assert_eq!(
+        position.least_valuable_attacker(helpers::sq_to_idx('b', '3'), Color::Black),
+        Some((34, Piece(PieceType::N, Color::Black)))
+    );
+    assert_eq!(position.least_valuable_attacker(helpers::sq_to_idx('a', '8'), Color::White), None);
+}
+
+#[test]
+fn draw_reason() {
+    let mut board = Board::from_fen(Fen::try_from("7k/8/6QK/8/8/8/8/8 w - - 0 1").unwrap());
+    board.make_move_san("Qf7").unwrap();
+    assert_eq!(board.game_result(), Some(super::GameResult::Draw(super::DrawType::Stalemate(Color::Black))));
+    let mut board = Board::default();
+    board.agree_draw().unwrap();
+    assert!(board.draw_agreed());
+    assert_eq!(board.game_result(), Some(super::GameResult::Draw(super::DrawType::Agreement)));
+}
+
+#[test]
+fn squares_between() {
+    use super::squares_between;
+
+    assert_eq!(squares_between(helpers::sq_to_idx('a', '1'), helpers::sq_to_idx('a', '4')), Some(vec![8, 16]));
+    assert_eq!(squares_between(helpers::sq_to_idx('h', '8'), helpers::sq_to_idx('e', '5')), Some(vec![54, 45]));
+    assert_eq!(squares_between(helpers::sq_to_idx('c', '3'), helpers::sq_to_idx('d', '3')), Some(vec![]));
+    assert_eq!(squares_between(helpers::sq_to_idx('a', '1'), helpers::sq_to_idx('b', '3')), None);
+    assert_eq!(squares_between(helpers::sq_to_idx('h', '1'), helpers::sq_to_idx('a', '2')), None);
+    assert_eq!(squares_between(0, 64), None);
+}
+
+#[test]
+fn time_control() {
+    use super::{GameResult, TimeControl, WinType};
+    use std::time::Duration;
+
+    let mut board = Board::default();
+    assert_eq!(board.time_remaining(Color::White), None);
+    board.set_time_control(TimeControl {
+        initial: Duration::from_secs(60),
+        increment: Duration::from_secs(2),
+        delay: Duration::from_secs(1),
+    });
+    board.make_move_san("e4").unwrap();
+    board.on_move_played(Duration::from_secs(11));
+    assert_eq!(board.time_remaining(Color::White), Some(Duration::from_secs(52)));
+    assert_eq!(board.time_remaining(Color::Black), Some(Duration::from_secs(60)));
+    board.make_move_san("e5").unwrap();
+    board.on_move_played(Duration::from_secs(61));
+    assert_eq!(board.time_remaining(Color::Black), Some(Duration::ZERO));
+    assert_eq!(board.flagged_side(), Some(Color::Black));
+    assert_eq!(board.game_result(), Some(GameResult::Wins(Color::White, WinType::Timeout)));
+    board.undo_move().unwrap();
+    assert!(board.is_ongoing());
+    assert_eq!(board.time_remaining(Color::Black), Some(Duration::from_secs(60)));
+    assert_eq!(board.time_remaining(Color::White), Some(Duration::from_secs(52)));
+    board.undo_move().unwrap();
+    assert_eq!(board.time_remaining(Color::White), Some(Duration::from_secs(60)));
+}
+
+#[test]
+fn clock() {
+    use super::{Clock, GameResult, WinType};
+    use std::time::Duration;
+
+    let mut board = Board::default();
+    assert_eq!(board.clock(), None);
+    board.make_move_san("e4").unwrap();
+    board.set_clock(Clock {
+        white: Duration::from_secs(30),
+        black: Duration::from_secs(10),
+        increment: Duration::from_secs(1),
+    });
+    assert_eq!(board.time_remaining(Color::Black), Some(Duration::from_secs(10)));
+    board.make_move_san("e5").unwrap();
+    board.on_move_played(Duration::from_secs(4));
+    let clock = board.clock().unwrap();
+    assert_eq!((clock.remaining(Color::White), clock.remaining(Color::Black)), (Duration::from_secs(30), Duration::from_secs(7)));
+    assert_eq!(clock.increment, Duration::from_secs(1));
+    board.undo_move().unwrap();
+    assert_eq!(board.clock().unwrap().black, Duration::from_secs(10));
+    board.make_move_san("e5").unwrap();
+    board.on_move_played(Duration::from_secs(10));
+    assert_eq!(board.game_result(), Some(GameResult::Wins(Color::White, WinType::Timeout)));
+    assert_eq!(Clock::new(Duration::from_secs(60), Duration::ZERO).remaining(Color::White), Duration::from_secs(60));
+}
+
+#[test]
+fn timeout_vs_insufficient_material() {
+    use super::{DrawType, GameResult, Termination, WinType};
+
+    let flag = |fen, side| {
+        let mut board = Board::from_fen(Fen::try_from(fen).unwrap());
+        board.flag(side).unwrap();
+        board
+    };
+    let board = flag("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", Color::Black);
+    assert_eq!(board.game_result(), Some(GameResult::Wins(Color::White, WinType::Timeout)));
+    assert_eq!(board.termination(), Termination::Timeout(Color::Black));
+    let board = flag("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", Color::White);
+    assert_eq!(board.game_result(), Some(GameResult::Draw(DrawType::TimeoutVsInsufficientMaterial)));
+    assert_eq!(board.termination(), Termination::TimeoutVsInsufficientMaterial(Color::White));
+    assert!(!board.draw_agreed());
+    let flipped = board.color_flipped();
+    assert_eq!(flipped.game_result(), Some(GameResult::Draw(DrawType::TimeoutVsInsufficientMaterial)));
+    assert_eq!(flipped.termination(), Termination::TimeoutVsInsufficientMaterial(Color::Black));
+    let flipped = flag("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", Color::Black).color_flipped();
+    assert_eq!(flipped.game_result(), Some(GameResult::Wins(Color::Black, WinType::Timeout)));
+    for fen in ["4k3/4p3/8/8/8/8/8/3NK3 w - - 0 1", "4k2q/8/8/8/8/8/8/3NK3 w - - 0 1", "3bk3/8/8/8/8/8/8/3BK3 w - - 0 1"] {
+        assert_eq!(flag(fen, Color::Black).game_result(), Some(GameResult::Wins(Color::White, WinType::Timeout)), "{fen}");
+    }
+    for (fen, could_checkmate) in [
+        ("4k3/8/8/8/8/8/8/3NK3 w - - 0 1", false),
+        ("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1", true),
+        ("2b1k3/8/8/8/8/8/8/3BK3 w - - 0 1", false),
+        ("2b1k3/8/8/8/8/8/8/2NBK3 w - - 0 1", true),
+    ] {
+        assert_eq!(Fen::try_from(fen).unwrap().position().could_checkmate(Color::White), could_checkmate, "{fen}");
+    }
+}
+
+#[test]
+fn double_check_king_moves() {
+    let position = Fen::try_from("4r1k1/8/8/8/8/5n2/3B4/R3K3 w Q - 0 1").unwrap().position().clone();
+    let king_moves = position.gen_non_illegal_moves_sq(4);
+    assert_eq!(position.gen_non_illegal_moves(), king_moves);
+    assert_eq!(king_moves, [Move(4, 3, None), Move(4, 5, None), Move(4, 13, None)]);
+    assert!((0..64).filter(|&sq| sq != 4).all(|sq| position.gen_non_illegal_moves_sq(sq).is_empty()));
+}
+
+#[test]
+fn quiet_positions() {
+    assert!(Board::default().position().is_quiet());
+    assert!(!Fen::try_from("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap().position().is_quiet());
+    assert!(!Fen::try_from("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap().position().is_quiet());
+    assert!(!Fen::try_from("4k3/8/8/8/8/8/8/r3K3 w - - 0 1").unwrap().position().is_quiet());
+}
+
+#[test]
+fn make_move_logged() {
+    let mut board = Board::default();
+    assert_eq!(board.make_move_logged(Move::from_uci("g1f3").unwrap()).unwrap(), "Nf3");
+    assert!(board.make_move_logged(Move::from_uci("g1f3").unwrap()).is_err());
+    assert_eq!(board.side_to_move(), Color::Black);
+}
+
+#[test]
+fn promotion_validity() {
+    assert!([PieceType::Q, PieceType::R, PieceType::B, PieceType::N].iter().all(|pt| pt.is_valid_promotion()));
+    assert!(!PieceType::K.is_valid_promotion() && !PieceType::P.is_valid_promotion());
+    assert!(Move::from_uci("e7e8n").is_ok());
+    assert!(Move::from_uci("e7e8k").is_err());
+    assert!(Move::from_uci("e7e8p").is_err());
+}
+
+#[test]
+fn branch_divergence() {
+    let mut main = Board::default();
+    main.make_moves_san("e4 e5 Nf3").unwrap();
+    let mut branch = main.branch();
+    assert_eq!(main.diverges_at(&branch), None);
+    branch.undo_move().unwrap();
+    assert_eq!(main.diverges_at(&branch), Some(2));
+    branch.make_moves_san("Bc4 Nc6").unwrap();
+    assert_eq!(main.diverges_at(&branch), Some(2));
+    assert_eq!(main.to_fen().to_string(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+}
+
+#[test]
+fn cannot_force_mate() {
+    let knnk = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/1N2K1N1 w - - 0 1").unwrap());
+    assert!(!knnk.is_insufficient_material());
+    assert!(knnk.cannot_force_mate());
+    let kknn = Board::from_fen(Fen::try_from("1n2k1n1/8/8/8/8/8/8/4K3 b - - 0 1").unwrap());
+    assert!(!kknn.is_insufficient_material() && kknn.cannot_force_mate());
+    assert!(Board::from_fen(Fen::try_from("4k1n1/8/8/8/8/8/8/4K1B1 w - - 0 1").unwrap()).cannot_force_mate());
+    assert!(!Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/1N2KBN1 w - - 0 1").unwrap()).cannot_force_mate());
+    assert!(!Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/4K2R w - - 0 1").unwrap()).cannot_force_mate());
+}
+
+#[test]
+fn position_builder_en_passant() {
+    use super::{PositionBuilder, PositionProblem};
+
+    let mut builder = PositionBuilder::new();
+    builder
+        .set_piece(4, Some(Piece(PieceType::K, Color::White)))
+        .set_piece(60, Some(Piece(PieceType::K, Color::Black)))
+        .set_piece(36, Some(Piece(PieceType::P, Color::White)))
+        .set_piece(35, Some(Piece(PieceType::P, Color::Black)))
+        .set_en_passant(Some(helpers::sq_to_idx('d', '6')));
+    let position = builder.build().unwrap();
+    assert_eq!(position.to_fen(), "4k3/8/8/3pP3/8/8/8/4K3 w - d6");
+    assert!(position.gen_non_illegal_moves().contains(&Move(36, 43, Some(SpecialMoveType::EnPassant))));
+    builder.set_side_to_move(Color::Black);
+    assert_eq!(builder.build().unwrap_err().0, [PositionProblem::InvalidEnPassantTarget(43)]);
+    assert_eq!(
+        PositionBuilder::new().build().unwrap_err().0,
+        [PositionProblem::MissingKing(Color::White), PositionProblem::MissingKing(Color::Black)]
+    );
+}
+
+#[test]
+fn position_builder_validation() {
+    use super::{PositionBuilder, PositionProblem};
+
+    let kings = || {
+        let mut builder = PositionBuilder::new();
+        builder.set_piece(4, Some(Piece(PieceType::K, Color::White))).set_piece(60, Some(Piece(PieceType::K, Color::Black)));
+        builder
+    };
+    assert_eq!(kings().build().unwrap().to_fen(), "4k3/8/8/8/8/8/8/4K3 w - -");
+    assert_eq!(kings().set_piece(60, None).build().unwrap_err().0, [PositionProblem::MissingKing(Color::Black)]);
+    assert_eq!(
+        kings().set_piece(0, Some(Piece(PieceType::K, Color::White))).build().unwrap_err().0,
+        [PositionProblem::TooManyKings(Color::White)]
+    );
+    assert_eq!(
+        kings().set_piece(58, Some(Piece(PieceType::P, Color::White))).build().unwrap_err().0,
+        [PositionProblem::PawnOnBackRank(58)]
+    );
+    assert_eq!(
+        kings().set_piece(12, Some(Piece(PieceType::R, Color::White))).set_side_to_move(Color::White).build().unwrap_err().0,
+        [PositionProblem::OpponentInCheck]
+    );
+    let mut builder = kings();
+    builder.set_piece(7, Some(Piece(PieceType::R, Color::White))).set_piece(56, Some(Piece(PieceType::R, Color::Black)));
+    let position = builder.set_castling_rights([Some(7), None, None, Some(56)]).build().unwrap();
+    assert_eq!(position.to_fen(), "r3k3/8/8/8/8/8/8/4K2R w Kq -");
+    assert_eq!(
+        builder.set_castling_rights([Some(0), None, Some(56), None]).build().unwrap_err().0,
+        [PositionProblem::InvalidCastlingRight(0), PositionProblem::InvalidCastlingRight(56)]
+    );
+}
+
+#[test]
+fn san_without_check_symbols() {
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5 Bc4 Nc6").unwrap();
+    let quiet = board.san_to_move("Qh5").unwrap();
+    let mut mating = board.clone();
+    mating.make_move_san("Qh5").unwrap();
+    mating.make_move_san("Nf6").unwrap();
+    let qxf7 = mating.san_to_move("Qxf7").unwrap();
+    assert_eq!(mating.move_to_san(qxf7).unwrap(), "Qxf7#");
+    assert_eq!(mating.move_to_san_plain(qxf7).unwrap(), "Qxf7");
+    let bxf7 = board.san_to_move("Bxf7").unwrap();
+    assert_eq!(board.move_to_san(bxf7).unwrap(), "Bxf7+");
+    assert_eq!(board.move_to_san_plain(bxf7).unwrap(), "Bxf7");
+    assert_eq!(board.move_to_san_plain(quiet).unwrap(), board.move_to_san(quiet).unwrap());
+    assert!(board.move_to_san_plain(Move(4, 20, None)).is_err());
+}
+
+#[test]
+fn long_algebraic_notation() {
+    let mut board = Board::default();
+    assert_eq!(board.move_to_lan(Move::from_uci("g1f3").unwrap()).unwrap(), "Ng1-f3");
+    assert_eq!(board.move_to_lan(Move::from_uci("e2e4").unwrap()).unwrap(), "e2-e4");
+    board.make_moves_san("e4 d5 Nf3 Nc6").unwrap();
+    assert_eq!(board.move_to_lan(Move::from_uci("e4d5").unwrap()).unwrap(), "e4xd5");
+    assert_eq!(board.lan_to_move("e4xd5").unwrap(), Move(28, 35, None));
+    assert_eq!(board.lan_to_move("e4-d5").unwrap(), Move(28, 35, None));
+    assert_eq!(board.lan_to_move("Bf1-b5").unwrap(), Move(5, 33, None));
+    assert!(board.lan_to_move("Bf1-a5").is_err());
+    assert!(board.lan_to_move("Nf1-b5").is_err());
+    board.make_moves_san("Bb5 Bg4").unwrap();
+    let castling = board.lan_to_move("O-O").unwrap();
+    assert_eq!(board.move_to_lan(castling).unwrap(), "O-O");
+    assert_eq!(board.lan_to_move("0-0").unwrap(), castling);
+    let board = Board::from_fen(Fen::try_from("6k1/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap());
+    let promotion = board.lan_to_move("e7-e8=Q").unwrap();
+    assert_eq!(promotion, Move(52, 60, Some(SpecialMoveType::Promotion(PieceType::Q))));
+    assert_eq!(board.move_to_lan(promotion).unwrap(), "e7-e8=Q+");
+    assert_eq!(board.lan_to_move("e7-e8=N").unwrap(), Move(52, 60, Some(SpecialMoveType::Promotion(PieceType::N))));
+}
+
+#[test]
+fn move_and_position_history() {
+    let mut board = Board::default();
+    assert_eq!(board.ply_count(), 0);
+    board.make_moves_san("e4 e5 Nf3 Nc6").unwrap();
+    assert_eq!(board.ply_count(), 4);
+    assert_eq!(board.move_history(), [Move(12, 28, None), Move(52, 36, None), Move(6, 21, None), Move(57, 42, None)]);
+    let positions = board.position_history();
+    assert_eq!(positions.len(), 4);
+    assert_eq!(&positions[0], Board::default().position());
+    for (i, position) in positions.iter().enumerate() {
+        let next = positions.get(i + 1).unwrap_or(board.position());
+        assert_eq!(&position.with_move_made(board.move_history()[i]).unwrap(), next);
+    }
+}
+
+#[test]
+fn occupied_squares() {
+    let position = Board::default().position().clone();
+    assert_eq!(position.pieces().count(), 32);
+    assert_eq!(position.pieces().next(), Some((0, Piece(PieceType::R, Color::White))));
+    for color in [Color::White, Color::Black] {
+        assert_eq!(position.pieces_of(color).count(), 16);
+        assert!(position.pieces_of(color).all(|(sq, piece)| piece.1 == color && position.content[sq] == Some(piece)));
+    }
+    assert_eq!(position.king_square(Color::White), 4);
+    assert_eq!(position.king_square(Color::Black), 60);
+}
+
+#[test]
+fn mating_piece() {
+    let position = |fen| Fen::try_from(fen).unwrap().position().clone();
+    assert_eq!(position("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").mating_piece(), Some((56, Piece(PieceType::R, Color::White))));
+    assert_eq!(position("6rk/5Npp/8/8/8/8/8/6K1 b - - 0 1").mating_piece(), Some((53, Piece(PieceType::N, Color::White))));
+    assert_eq!(position("R5k1/5pp1/7p/8/8/8/8/6K1 b - - 0 1").mating_piece(), None);
+    assert_eq!(position("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1").mating_piece(), None);
+    assert_eq!(Board::default().position().mating_piece(), None);
+}
+
+#[test]
+fn move_between() {
+    let start = Board::default().to_fen();
+    let e4 = Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+    assert_eq!(Board::move_between(&start, &e4), Some(Move(12, 28, None)));
+    let e4_no_ep = Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+    assert_eq!(Board::move_between(&start, &e4_no_ep), Some(Move(12, 28, None)));
+    assert_eq!(Board::move_between(&e4, &start), None);
+    assert_eq!(Board::move_between(&start, &start), None);
+    let unrelated = Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    assert_eq!(Board::move_between(&start, &unrelated), None);
+}
+
+#[test]
+fn from_uci_position() {
+    use super::UciPositionError;
+
+    assert_eq!(Board::from_uci_position("position startpos").unwrap(), Board::default());
+    let mut expected = Board::default();
+    expected.make_moves_uci("e2e4 e7e5 g1f3").unwrap();
+    assert_eq!(Board::from_uci_position("position startpos moves e2e4 e7e5 g1f3").unwrap(), expected);
+    let board = Board::from_uci_position("position fen 4k3/8/8/8/8/8/4P3/4K3 w - - 0 1 moves e2e4 e8d7").unwrap();
+    assert_eq!(board.to_fen().to_string(), "8/3k4/8/8/4P3/8/8/4K3 w - - 1 2");
+    assert_eq!(
+        Board::from_uci_position("position fen 4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap().to_fen().to_string(),
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"
+    );
+    assert!(matches!(Board::from_uci_position("position startpos moves e2e4 e7e5 e1e3"), Err(UciPositionError::IllegalMove(m)) if m == "e1e3"));
+    assert!(matches!(Board::from_uci_position("position fen 4k3/8/8/8 w - - 0 1 moves e2e4"), Err(UciPositionError::Fen(_))));
+    assert!(matches!(Board::from_uci_position("position startpos e2e4"), Err(UciPositionError::Command(_))));
+    assert!(matches!(Board::from_uci_position("go depth 5"), Err(UciPositionError::Command(_))));
+}
+
+#[test]
+fn state_hash() {
+    let (mut a, mut b) = (Board::default(), Board::default());
+    a.make_moves_san("Nf3 Nf6 Nc3 Nc6").unwrap();
+    b.make_moves_san("Nc3 Nc6 Nf3 Nf6").unwrap();
+    assert_ne!(a, b);
+    assert_eq!(a.state_hash(), b.state_hash());
+    assert_eq!(a.state_hash(), Board::from_fen(a.to_fen()).state_hash());
+    let mut c = Board::default();
+    c.make_moves_san("Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Nc3 Nc6").unwrap();
+    assert_eq!(c.position(), a.position());
+    assert_ne!(c.state_hash(), a.state_hash());
+    assert_ne!(Board::default().state_hash(), Board::default().position().zobrist_hash());
+}
+
+#[test]
+fn mobility_by_piece() {
+    use std::collections::HashMap;
+
+    let position = Board::default().position().clone();
+    let expected = HashMap::from([(PieceType::P, 16), (PieceType::N, 4)]);
+    assert_eq!(position.mobility_by_piece(Color::White), expected);
+    assert_eq!(position.mobility_by_piece(Color::Black), expected);
+    let position = Fen::try_from("4k3/8/8/8/8/8/4r3/R3K3 w Q - 0 1").unwrap().position().clone();
+    assert_eq!(position.mobility_by_piece(Color::White), HashMap::from([(PieceType::K, 6), (PieceType::R, 10)]));
+    assert_eq!(position.mobility_by_piece(Color::Black), HashMap::from([(PieceType::K, 5), (PieceType::R, 13)]));
+}
+
+#[test]
+fn pretty_print_options() {
+    use super::PrintOptions;
+
+    let board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap());
+    assert_eq!(board.pretty_print_opts(Color::White, PrintOptions::default()), board.pretty_print(Color::White, false));
+    let bare = board.pretty_print_opts(Color::White, PrintOptions { ascii: true, coordinates: false });
+    let lines: Vec<_> = bare.lines().collect();
+    assert_eq!(lines.len(), 17);
+    assert_eq!(lines[0], "┌───┬───┬───┬───┬───┬───┬───┬───┐");
+    assert_eq!(lines[1], "│   │   │   │   │ k │   │   │   │");
+    assert_eq!(lines[15], "│   │   │   │   │ K │   │   │   │");
+    assert!(board.pretty_print(Color::White, true).ends_with("\n    a   b   c   d   e   f   g   h  "));
+}
+
+#[test]
+fn stalemate_risk() {
+    let board = Board::from_fen(Fen::try_from("k7/8/1K6/8/8/8/8/2Q5 w - - 0 1").unwrap());
+    assert_eq!(board.stalemate_risk(), [Move(2, 29, None), Move(2, 50, None)]);
+    assert!(Board::default().stalemate_risk().is_empty());
+}
+
+#[test]
+fn play_movetext() {
+    let mut board = Board::default();
+    board
+        .play_movetext("1. e4 e5 2. Nf3 {the most common move} Nc6 (2... d6 3. d4) 3. Bb5 $1 a6 4.Ba4!? ; the main line\n4... Nf6 5. 0-0 1/2-1/2")
+        .unwrap();
+    let mut expected = Board::default();
+    expected.make_moves_san("e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O").unwrap();
+    assert_eq!(board, expected);
+    assert!(board.play_movetext("5... Be7 6. Re1 b5 7. Bb3 8. Qxf7").is_err());
+    assert_eq!(board, expected);
+    let mut board = Board::default();
+    board.play_movetext("1. e4 a6 2. e5 f5 3. exf6 e.p. g6 4. f7+ *").unwrap();
+    let mut expected = Board::default();
+    expected.make_moves_san("e4 a6 e5 f5 exf6 g6 f7+").unwrap();
+    assert_eq!(board, expected);
+}
+
+#[test]
+fn attacked_by_pawn() {
+    let position = Fen::try_from("4k3/p7/8/8/3p3P/8/8/4K3 w - - 0 1").unwrap().position().clone();
+    let sq = |s: &str| helpers::sq_to_idx(s.chars().next().unwrap(), s.chars().nth(1).unwrap());
+    assert!(position.is_attacked_by_pawn(sq("g5"), Color::White));
+    assert!(!position.is_attacked_by_pawn(sq("a6"), Color::White));
+    assert!(!position.is_attacked_by_pawn(sq("h5"), Color::White));
+    assert!(position.is_attacked_by_pawn(sq("b6"), Color::Black));
+    assert!(!position.is_attacked_by_pawn(sq("h6"), Color::Black));
+    assert!(position.is_attacked_by_pawn(sq("c3"), Color::Black) && position.is_attacked_by_pawn(sq("e3"), Color::Black));
+    assert!(!position.is_attacked_by_pawn(sq("d3"), Color::Black));
+    assert!(!position.is_attacked_by_pawn(sq("c5"), Color::Black));
+}
+
+#[test]
+fn termination() {
+    use super::Termination;
+
+    let mut board = Board::default();
+    assert_eq!(board.termination(), Termination::Ongoing);
+    board.make_moves_san("f3 e5 g4 Qh4#").unwrap();
+    assert_eq!(board.termination(), Termination::Checkmate(Color::White));
+    let mut board = Board::default();
+    board.resign(Color::Black).unwrap();
+    assert_eq!(board.termination(), Termination::Resignation(Color::Black));
+    let mut board = Board::default();
+    board.flag(Color::White).unwrap();
+    assert_eq!(board.termination(), Termination::Timeout(Color::White));
+    let mut board = Board::default();
+    board.agree_draw().unwrap();
+    assert_eq!(board.termination(), Termination::Agreement);
+    let board = Board::from_fen(Fen::try_from("k7/8/1K6/8/8/8/8/8 w - - 0 1").unwrap());
+    assert_eq!(board.termination(), Termination::InsufficientMaterial);
+    let board = Board::from_fen(Fen::try_from("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1").unwrap());
+    assert_eq!(board.termination(), Termination::Stalemate(Color::Black));
+}
+
+#[test]
+fn check_evasions() {
+    assert_eq!(Board::default().position().gen_check_evasions(), None);
+    for fen in [
+        "4k3/8/8/8/1b6/8/4P3/R2QK2R w KQ - 0 1",
+        "4k3/8/8/8/3p4/4K3/8/8 w - - 0 1",
+        "4k3/8/8/3pP3/2K5/8/8/8 w - - 0 1",
+        "4k3/8/8/8/8/5n2/8/r3K2R w K - 0 1",
+        "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+    ] {
+        let position = Fen::try_from(fen).unwrap().position().clone();
+        let expected: Vec<_> = (0..64).flat_map(|i| position.gen_non_illegal_moves_sq(i)).collect();
+        let evasions = position.gen_check_evasions().expect(fen);
+        assert!(evasions.windows(2).all(|w| (w[0].0, w[0].1) <= (w[1].0, w[1].1)), "{fen}");
+        assert_eq!(evasions, expected, "{fen}");
+    }
+    let position = Fen::try_from("4k3/8/8/3pP3/2K5/8/8/8 w - d6 0 1").unwrap().position().clone();
+    assert!(position.gen_check_evasions().unwrap().contains(&Move(36, 43, Some(SpecialMoveType::EnPassant))));
+}
+
+#[test]
+fn set_side_to_move() {
+    use super::{DrawType, GameResult, PositionProblem};
+
+    let mut board = Board::default();
+    board.make_move_san("e4").unwrap();
+    board.set_side_to_move(Color::White).unwrap();
+    assert_eq!(board.side_to_move(), Color::White);
+    assert_eq!(board.to_fen().to_string(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 1");
+    let mut board = Board::from_fen(Fen::try_from("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1").unwrap());
+    assert!(!board.is_ongoing());
+    board.set_side_to_move(Color::White).unwrap();
+    assert!(board.is_ongoing());
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/4K2R b - - 0 1").unwrap());
+    board.make_move_san("Kd8").unwrap();
+    board.make_move_san("Rh8+").unwrap();
+    assert_eq!(board.set_side_to_move(Color::White).unwrap_err().0, [PositionProblem::OpponentInCheck]);
+    assert_eq!(board.side_to_move(), Color::Black);
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 150 90").unwrap());
+    board.set_side_to_move(Color::Black).unwrap();
+    assert!(!board.is_ongoing());
+    assert_eq!(board.game_result(), Some(GameResult::Draw(DrawType::SeventyFiveMoveRule)));
+}
+
+#[test]
+fn scoresheet() {
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5 Nf3 Nc6 Bb5").unwrap();
+    let row = |n, w: Option<&str>, b: Option<&str>| (n, w.map(|s| s.to_owned()), b.map(|s| s.to_owned()));
+    assert_eq!(board.movetext_rows(), [row(1, Some("e4"), Some("e5")), row(2, Some("Nf3"), Some("Nc6")), row(3, Some("Bb5"), None)]);
+    assert_eq!(board.scoresheet(), [row(1, Some("e4"), Some("e5")), row(2, Some("♘f3"), Some("♞c6")), row(3, Some("♗b5"), None)]);
+    let mut board = Board::from_fen(Fen::try_from("4k3/1P6/8/8/8/8/8/4K3 b - - 0 40").unwrap());
+    board.make_moves_san("Kd7 b8=Q").unwrap();
+    assert_eq!(board.scoresheet(), [row(40, None, Some("♚d7")), row(41, Some("b8=♕"), None)]);
+}
+
+#[test]
+fn equivalent_under_symmetry() {
+    let position = |fen| Fen::try_from(fen).unwrap().position().clone();
+    let start = Board::default().position().clone();
+    assert!(start.equivalent_under_symmetry(&start));
+    assert!(start.equivalent_under_symmetry(&start.color_flipped()));
+    let kpk = position("8/8/8/4k3/8/8/1P6/1K6 w - - 0 1");
+    assert!(kpk.equivalent_under_symmetry(&position("8/8/8/3k4/8/8/6P1/6K1 w - - 0 1")));
+    assert!(kpk.equivalent_under_symmetry(&position("6k1/6p1/8/8/3K4/8/8/8 b - - 0 1")));
+    assert!(!kpk.equivalent_under_symmetry(&position("1K6/1P6/8/8/4k3/8/8/8 w - - 0 1")));
+    let krk = position("8/8/8/4k3/8/8/1R6/1K6 w - - 0 1");
+    assert!(krk.equivalent_under_symmetry(&position("1K6/1R6/8/8/4k3/8/8/8 w - - 0 1")));
+    assert!(!krk.equivalent_under_symmetry(&position("1K6/1R6/8/8/4k3/8/8/8 b - - 0 1")));
+    let castling = position("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1");
+    assert!(!castling.equivalent_under_symmetry(&position("3k4/8/8/8/8/8/8/3K3R w - - 0 1")));
+    assert!(position("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").equivalent_under_symmetry(&position("3k4/8/8/8/8/8/8/3K3R w - - 0 1")));
+}
+
+#[test]
+fn moves_uci() {
+    let mut board = Board::default();
+    assert!(board.moves_uci().is_empty());
+    board.make_moves_san("e4 d5 exd5 Qxd5 Nc3 Qa5 Bc4 Nf6 Nf3 Bg4 O-O").unwrap();
+    assert_eq!(board.moves_uci(), ["e2e4", "d7d5", "e4d5", "d8d5", "b1c3", "d5a5", "f1c4", "g8f6", "g1f3", "c8g4", "e1g1"]);
+}
+
+#[test]
+fn closed_positions() {
+    assert!(!Board::default().position().is_closed());
+    let french = Fen::try_from("r1bqkb1r/pp1n1ppp/2n1p3/2ppP3/3P1P2/2N2N2/PPP3PP/R1BQKB1R b KQkq - 0 7").unwrap();
+    assert!(!french.position().is_closed());
+    let kings_indian = Fen::try_from("r1bq1rk1/pp1nn1bp/3p2p1/2pPpp2/2P1P3/2N2N2/PP2BPPP/R1BQ1RK1 w - c6 0 10").unwrap();
+    assert!(kings_indian.position().is_closed());
+}
+
+#[test]
+fn epd() {
+    use super::{errors::InvalidEpdError, Epd};
+
+    let epd = Epd::try_from(r#"r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - bm Qxf7#; id "scholar's mate; 1"; fmvn 4;"#).unwrap();
+    assert_eq!(epd.operation("bm"), Some("Qxf7#"));
+    assert_eq!(epd.operation("id"), Some("scholar's mate; 1"));
+    assert_eq!(epd.fen().to_string(), "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 0 4");
+    assert_eq!(
+        epd.to_string(),
+        r#"r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - bm Qxf7#; fmvn 4; id "scholar's mate; 1";"#
+    );
+    let mut board = Board::from_epd(&epd.to_string()).unwrap();
+    board.make_move_san("Qxf7#").unwrap();
+    assert!(board.is_checkmate());
+    assert_eq!(
+        Board::from_epd("8/8/8/8/8/8/8/8 w - -").unwrap_err().to_string(),
+        "Invalid EPD board fields: Invalid FEN board data '8/8/8/8/8/8/8/8': a valid chess position must have one white king and one black king"
+    );
+    assert!(matches!(Epd::try_from("4k3/8/8/8/8/8/8/4K3 w -"), Err(InvalidEpdError::FourFields)));
+    assert!(matches!(Epd::try_from("4k3/8/8/8/8/8/8/4K3 w - - hmvc x;"), Err(InvalidEpdError::Operation(_))));
+    assert!(matches!(Epd::try_from("4k3/8/8/8/8/8/8/4K3 w - - 3bm e4;"), Err(InvalidEpdError::Operation(_))));
+    assert!(matches!(Epd::try_from("4k3/8/8/8/8/8/8/4K3 w - - bm e4;"), Err(InvalidEpdError::Operation(op)) if op == "bm e4"));
+}
+
+#[test]
+fn epd_test_suite_operations() {
+    use super::Epd;
+
+    let epd = Epd::try_from(r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#).unwrap();
+    assert_eq!(epd.id(), Some("WAC.001"));
+    assert_eq!(epd.best_moves(), [Move(22, 46, None)]);
+    assert!(epd.avoid_moves().is_empty());
+    let epd = Epd::try_from(r#"8/7p/5k2/5p2/p1p2P2/Pr1pPK2/1P1R3P/8 b - - bm Rxb2; id "WAC.002";"#).unwrap();
+    assert_eq!(epd.id(), Some("WAC.002"));
+    assert_eq!(epd.best_moves(), [Move(17, 9, None)]);
+    let epd = Epd::try_from(r#"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - bm e4 d4 Nf3; am f3 g4; id "start";"#).unwrap();
+    assert_eq!(epd.best_moves(), [Move(12, 28, None), Move(11, 27, None), Move(6, 21, None)]);
+    assert_eq!(epd.avoid_moves(), [Move(13, 21, None), Move(14, 30, None)]);
+}
+
+#[test]
+fn control_map() {
+    let control = Board::default().position().control();
+    assert_eq!(control[0], [0, 1, 1, 1, 1, 1, 1, 0]);
+    assert_eq!(control[2], [2, 2, 3, 2, 2, 3, 2, 2]);
+    assert_eq!(control[3], [0; 8]);
+    assert_eq!(control[5], [-2, -2, -3, -2, -2, -3, -2, -2]);
+    let control = Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap().position().control();
+    assert_eq!(control[7], [1, 0, 0, -1, 0, -1, 0, 0]);
+    assert_eq!(control[0][0], 0);
+    assert_eq!(control[1][0..6], [1, 0, 0, 1, 1, 1]);
+}
+
+#[test]
+fn san_uci_conversion() {
+    use super::errors::InvalidUciMoveError;
+
+    let mut board = Board::default();
+    assert_eq!(board.san_to_uci("Nf3").unwrap(), "g1f3");
+    assert_eq!(board.uci_to_san("e2e4").unwrap(), "e4");
+    assert!(board.san_to_uci("Ke2").is_err());
+    assert!(matches!(board.uci_to_san("e2e5"), Err(InvalidUciMoveError::IllegalMove(_))));
+    assert!(matches!(board.uci_to_san("e2"), Err(InvalidUciMoveError::InvalidUci(_))));
+    board.make_moves_san("e4 e5 Nf3 Nc6 Bc4 Nf6").unwrap();
+    assert_eq!(board.uci_to_san("e1g1").unwrap(), "O-O");
+    assert_eq!(board.san_to_uci("O-O").unwrap(), "e1g1");
+}
+
+#[test]
+fn same_position() {
+    let (mut a, mut b) = (Board::default(), Board::default());
+    a.make_moves_san("d4 Nf6 Nf3 d5").unwrap();
+    b.make_moves_san("Nf3 Nf6 d4 d5").unwrap();
+    assert!(a.same_position(&b, true));
+    assert_ne!(a, b);
+    let mut c = Board::default();
+    c.make_moves_san("Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 d4 d5").unwrap();
+    assert!(a.same_position(&c, false));
+    assert!(!a.same_position(&c, true));
+    assert!(!a.same_position(&Board::default(), false));
+}
+
+#[test]
+fn legal_moves_from() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/8/8/8/k2Pp2Q/8/8/3K4 b - d3 0 1",
+        "4k3/8/8/8/1b6/8/4P3/R2QK2R w KQ - 0 1",
+    ] {
+        let position = Fen::try_from(fen).unwrap().position().clone();
+        let from_squares: Vec<Vec<_>> = (0..64).map(|sq| position.legal_moves_from(sq)).collect();
+        let all = position.gen_non_illegal_moves();
+        for (sq, moves) in from_squares.into_iter().enumerate() {
+            assert_eq!(moves, all.iter().copied().filter(|m| m.0 == sq).collect::<Vec<_>>(), "{fen}");
+            assert_eq!(position.legal_moves_from(sq), moves);
+        }
+    }
+}
+
+#[test]
+fn threefold_repetition_count() {
+    let mut board = Board::default();
+    let shuffle = ["Nf3", "Nf6", "Ng1", "Ng8"];
+    let mut flags = Vec::new();
+    for san in shuffle.iter().cycle().take(12) {
+        board.make_move_san(san).unwrap();
+        flags.push(board.is_threefold_repetition());
+    }
+    // the starting position occurs for the third time after the 8th ply, and the position after 1. Nf3 after the 9th
+    assert_eq!(flags.iter().position(|&f| f), Some(7));
+    assert!(flags[7..].iter().all(|&f| f));
+    assert!(!board.is_fivefold_repetition());
+    board.make_moves_san("Nf3 Nf6 Ng1 Ng8").unwrap();
+    assert!(board.is_fivefold_repetition());
+    assert!(board.is_game_over());
+}
+
+#[test]
+fn repetition_respects_castling_and_en_passant() {
+    let mut board = Board::default();
+    board.make_moves_san("Nf3 Nf6 Rg1 Ng8 Rh1 Nf6 Rg1 Ng8 Rh1 Nf6").unwrap();
+    // the arrangement after 1. Nf3 Nf6 has occurred three times, but white lost the kingside castling right after the first
+    assert!(!board.is_threefold_repetition());
+    board.make_moves_san("Rg1 Ng8 Rh1 Nf6").unwrap();
+    assert!(board.is_threefold_repetition());
+    let mut board = Board::default();
+    board.make_moves_san("e4 Nf6 Nf3 Ng8 Ng1 Nf6 Nf3 Ng8 Ng1").unwrap();
+    // the en passant target square on e3 after 1. e4 cannot be captured onto, so it does not make the position different
+    assert!(board.is_threefold_repetition());
+}
+
+#[test]
+fn draw_offers() {
+    use super::{DrawType, GameOverError, GameResult};
+
+    let mut board = Board::default();
+    assert!(matches!(board.accept_draw(), Err(GameOverError::NoDrawOffer)));
+    board.offer_draw(Color::White).unwrap();
+    assert_eq!(board.pending_draw_offer(), Some(Color::White));
+    board.make_move_san("e4").unwrap();
+    assert_eq!(board.pending_draw_offer(), None);
+    assert!(board.is_ongoing());
+    board.offer_draw(Color::White).unwrap();
+    board.make_move_san("e5").unwrap();
+    assert_eq!(board.pending_draw_offer(), Some(Color::White));
+    board.make_move_san("Nf3").unwrap();
+    assert_eq!(board.pending_draw_offer(), None);
+    board.offer_draw(Color::White).unwrap();
+    board.decline_draw();
+    assert_eq!(board.pending_draw_offer(), None);
+    board.offer_draw(Color::Black).unwrap();
+    board.accept_draw().unwrap();
+    assert_eq!(board.game_result(), Some(GameResult::Draw(DrawType::Agreement)));
+    assert_eq!(board.pending_draw_offer(), None);
+    assert!(matches!(board.offer_draw(Color::White), Err(GameOverError::DrawOffer)));
+    assert!(matches!(board.accept_draw(), Err(GameOverError::AgreementDraw)));
+}
+
+#[test]
+fn claim_draw() {
+    use super::{DrawType, GameOverError, GameResult, Termination};
+
+    let mut board = Board::default();
+    assert_eq!(board.can_claim_draw(), None);
+    assert!(matches!(board.claim_draw(), Err(GameOverError::NoDrawToClaim)));
+    assert!(board.is_ongoing());
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 98 60").unwrap());
+    board.make_move_san("Ra2").unwrap();
+    assert_eq!(board.can_claim_draw(), None);
+    board.make_move_san("Kd7").unwrap();
+    assert_eq!(board.can_claim_draw(), Some(DrawType::FiftyMoveRule));
+    board.make_move_san("Ra3").unwrap();
+    assert_eq!(board.can_claim_draw(), Some(DrawType::FiftyMoveRule));
+    board.claim_draw().unwrap();
+    assert_eq!(board.game_result(), Some(GameResult::Draw(DrawType::FiftyMoveRule)));
+    assert_eq!(board.termination(), Termination::FiftyMove);
+    assert_eq!(board.can_claim_draw(), None);
+    assert!(matches!(board.claim_draw(), Err(GameOverError::DrawClaim)));
+}
+
+#[test]
+fn result_types_reachable() {
+    use super::{DrawType, GameResult, WinType};
+
+    let mut results = Vec::new();
+    let mut board = Board::default();
+    board.make_moves_san("f3 e5 g4 Qh4#").unwrap();
+    results.push(board.game_result().unwrap());
+    let mut board = Board::default();
+    board.resign(Color::White).unwrap();
+    results.push(board.game_result().unwrap());
+    let mut board = Board::default();
+    board.make_moves_san("Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8").unwrap();
+    results.push(board.game_result().unwrap());
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 149 100").unwrap());
+    board.make_move_san("Ra2").unwrap();
+    results.push(board.game_result().unwrap());
+    for fen in ["k7/2Q5/1K6/8/8/8/8/8 b - - 0 1", "8/8/8/8/8/6k1/5q2/7K w - - 0 1", "4k3/8/8/8/8/8/8/4K3 w - - 0 1"] {
+        results.push(Board::from_fen(Fen::try_from(fen).unwrap()).game_result().unwrap());
+    }
+    let mut board = Board::default();
+    board.agree_draw().unwrap();
+    results.push(board.game_result().unwrap());
+    let mut board = Board::default();
+    board.flag(Color::White).unwrap();
+    results.push(board.game_result().unwrap());
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap());
+    board.flag(Color::White).unwrap();
+    results.push(board.game_result().unwrap());
+    let mut board = Board::default();
+    board.make_moves_san("Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8").unwrap();
+    board.claim_draw().unwrap();
+    results.push(board.game_result().unwrap());
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 99 60").unwrap());
+    board.make_move_san("Ra2").unwrap();
+    board.claim_draw().unwrap();
+    results.push(board.game_result().unwrap());
+    for win_type in WinType::all() {
+        assert!(results.iter().any(|r| matches!(r, GameResult::Wins(_, w) if *w == win_type)), "{win_type:?}");
+    }
+    for draw_type in DrawType::all() {
+        assert!(results.contains(&GameResult::Draw(draw_type)), "{draw_type:?}");
+    }
+}
+
+#[test]
+fn opening_book() {
+    use super::OpeningBook;
+
+    let mut book = OpeningBook::new();
+    let mut board = Board::default();
+    for san in ["e4", "c5", "Nf3"] {
+        book.insert(board.position(), board.san_to_move(san).unwrap());
+        board.make_move_san(san).unwrap();
+    }
+    book.insert(Board::default().position(), Move::from_uci("d2d4").unwrap());
+    assert_eq!(book.len(), 3);
+    let mut board = Board::default();
+    assert_eq!(board.book_move(&book), Some(Move(12, 28, None)));
+    assert!(board.is_book_move(&book, Move::from_uci("d2d4").unwrap()));
+    assert!(!board.is_book_move(&book, Move::from_uci("c2c4").unwrap()));
+    board.make_moves_san("e4 c5").unwrap();
+    assert_eq!(board.book_move(&book), Some(Move(6, 21, None)));
+    board.make_move_san("Nc3").unwrap();
+    assert_eq!(board.book_move(&book), None);
+}
+
+#[test]
+fn pawn_shield_count() {
+    let position = Board::default().position().clone();
+    assert_eq!(position.pawn_shield_count(Color::White), 3);
+    assert_eq!(position.pawn_shield_count(Color::Black), 3);
+    let position = Fen::try_from("2kr3r/ppp2ppp/8/8/8/6P1/PP3P1P/5RK1 w - - 0 1").unwrap().position().clone();
+    assert_eq!(position.pawn_shield_count(Color::White), 2);
+    assert_eq!(position.pawn_shield_count(Color::Black), 3);
+    let position = Fen::try_from("1k6/8/8/8/8/8/1p6/K7 w - - 0 1").unwrap().position().clone();
+    assert_eq!(position.pawn_shield_count(Color::White), 0);
+    assert_eq!(position.pawn_shield_count(Color::Black), 0);
+}
+
+#[test]
+fn replay() {
+    let mut board = Board::default();
+    assert_eq!(board.replay().count(), 0);
+    board.make_moves_san("e4 e5 Nf3").unwrap();
+    let mut replayed = Board::default();
+    for (move_, position) in board.replay() {
+        replayed.make_move(move_).unwrap();
+        assert_eq!(replayed.position(), &position);
+    }
+    assert_eq!(board.replay().last().unwrap().1, *board.position());
+}
+
+#[test]
+fn underpromotion_mate() {
+    let board = Board::from_fen(Fen::try_from("6r1/5Ppp/6pk/6pp/8/8/8/K7 w - - 0 1").unwrap());
+    let legal_moves = board.gen_legal_moves();
+    for piece_type in [PieceType::Q, PieceType::R, PieceType::B, PieceType::N] {
+        for dest in [61, 62] {
+            assert!(legal_moves.contains(&Move(53, dest, Some(SpecialMoveType::Promotion(piece_type)))));
+        }
+    }
+    assert_eq!(board.mating_moves(), [Move(53, 62, Some(SpecialMoveType::Promotion(PieceType::N)))]);
+    assert!(board.requires_underpromotion());
+    let mut board = Board::default();
+    board.make_moves_san("f3 e5 g4").unwrap();
+    assert_eq!(board.mating_moves(), [Move(59, 31, None)]);
+    assert!(!board.requires_underpromotion());
+    assert!(!Board::default().requires_underpromotion());
+}
+
+#[test]
+fn is_move_legal() {
+    let specs = [
+        None,
+        Some(SpecialMoveType::Unclear),
+        Some(SpecialMoveType::CastlingKingside),
+        Some(SpecialMoveType::CastlingQueenside),
+        Some(SpecialMoveType::EnPassant),
+        Some(SpecialMoveType::Promotion(PieceType::Q)),
+        Some(SpecialMoveType::Promotion(PieceType::N)),
+    ];
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/8/8/8/k2Pp2Q/8/8/3K4 b - d3 0 1",
+        "4k3/8/8/8/1b6/8/4P3/R2QK2R w KQ - 0 1",
+        "6r1/5Ppp/6pk/6pp/8/8/8/K7 w - - 0 1",
+        "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2",
+    ] {
+        let position = Fen::try_from(fen).unwrap().position().clone();
+        let legal_moves = position.gen_non_illegal_moves();
+        for src in 0..64 {
+            for dest in 0..64 {
+                for spec in specs {
+                    let move_ = Move(src, dest, spec);
+                    assert_eq!(position.is_move_legal(move_), helpers::as_legal(move_, &legal_moves).is_some(), "{fen} {move_:?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn material_balance_history() {
+    let mut board = Board::default();
+    assert_eq!(board.material_balance_history(), [0]);
+    board.make_moves_san("e4 d5 exd5 Qxd5 Nc3 Qxa2 Rxa2").unwrap();
+    assert_eq!(board.material_balance_history(), [0, 0, 0, 1, 0, 0, -1, 8]);
+    assert_eq!(board.position().material_balance(), 8);
+}
+
+#[test]
+fn material_count_and_balance() {
+    use super::MaterialCount;
+
+    let position = Board::default().position().clone();
+    assert_eq!(position.material_balance(), 0);
+    let expected = MaterialCount {
+        pawns: 8,
+        knights: 2,
+        bishops: 2,
+        rooks: 2,
+        queens: 1,
+    };
+    assert_eq!(position.material_count(Color::White), expected);
+    assert_eq!(position.material_count(Color::Black), expected);
+    let position = Fen::try_from("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/1NBQKBNR w Kkq - 0 1").unwrap().position().clone();
+    assert_eq!(position.material_balance(), 4);
+    assert_eq!(position.material_count(Color::White).rooks, 1);
+    assert_eq!(position.material_count(Color::Black).queens, 0);
+    assert_eq!(position.material_count(Color::Black).count(PieceType::R), 2);
+    assert_eq!(position.material_count(Color::Black).count(PieceType::K), 0);
+}
+
+#[test]
+fn color_index() {
+    for color in [Color::White, Color::Black] {
+        assert_eq!(Color::from_index(color.index()), Some(color));
+    }
+    assert_eq!(Color::White.index(), 0);
+    assert_eq!(Color::Black.index(), 1);
+    assert_eq!(Color::from_index(2), None);
+}
+
+#[test]
+fn move_causes_threefold() {
+    let mut board = Board::default();
+    board.make_moves_san("Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1").unwrap();
+    assert!(board.move_causes_threefold(board.san_to_move("Ng8").unwrap()).unwrap());
+    assert!(!board.move_causes_threefold(board.san_to_move("Nh5").unwrap()).unwrap());
+    board.undo_move().unwrap();
+    assert!(!board.move_causes_threefold(board.san_to_move("Ng1").unwrap()).unwrap());
+    assert!(board.move_causes_threefold(Move(0, 63, None)).is_err());
+}
+
+#[test]
+fn to_pgn() {
+    use super::PgnTags;
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+
+    let mut board = Board::default();
+    board.make_moves_san("f3 e5 g4 Qh4#").unwrap();
+    let tags = PgnTags {
+        event: Some(r#"The "Fool's" Cup \ 1"#.to_owned()),
+        date: NaiveDate::from_ymd_opt(2024, 3, 9),
+        white: Some("Fool".to_owned()),
+        eco: Some("A00".to_owned()),
+        extra: HashMap::from([("TimeControl".to_owned(), "300".to_owned()), ("Annotator".to_owned(), "Anon".to_owned())]),
+        ..Default::default()
+    };
+    assert_eq!(
+        board.to_pgn(&tags),
+        r#"[Event "The \"Fool's\" Cup \\ 1"]
+[Site "?"]
+[Date "2024.03.09"]
+[Round "?"]
+[White "Fool"]
+[Black "?"]
+[Result "0-1"]
+[ECO "A00"]
+[Annotator "Anon"]
+[TimeControl "300"]
+
+1. f3 e5 2. g4 Qh4# 0-1"#
+    );
+    let board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/4K2R b K - 0 1").unwrap());
+    let pgn = board.to_pgn(&PgnTags::default());
+    assert!(pgn.contains("[Date \"????.??.??\"]\n"));
+    assert!(pgn.ends_with("[Result \"*\"]\n[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/4K2R b K - 0 1\"]\n\n*"));
+    assert!(tags.to_string().starts_with("[Event \"The \\\"Fool's\\\" Cup \\\\ 1\"]\n[Site \"?\"]\n"));
+    assert!(tags.to_string().ends_with("[ECO \"A00\"]\n[Annotator \"Anon\"]\n[TimeControl \"300\"]"));
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5 Nf3 Nc6 Bb5 a6 Ba4 Nf6 O-O Be7 Re1 b5 Bb3 d6 c3 O-O h3 Nb8 d4 Nbd7 c4 c6").unwrap();
+    let pgn = board.to_pgn(&PgnTags::default());
+    let movetext: Vec<_> = pgn.split("\n\n").nth(1).unwrap().lines().collect();
+    assert!(movetext.len() > 1 && movetext.iter().all(|line| line.len() <= 80));
+    assert_eq!(Board::from_pgn(&pgn).unwrap(), board);
+}
+
+#[test]
+fn bishops_on_color() {
+    let position = Board::default().position().clone();
+    for color in [Color::White, Color::Black] {
+        assert_eq!(position.bishops_on_color(color, Color::White), 1);
+        assert_eq!(position.bishops_on_color(color, Color::Black), 1);
+    }
+    let position = Fen::try_from("4k3/8/8/3b4/8/8/8/2B1KB2 w - - 0 1").unwrap().position().clone();
+    assert_eq!(position.bishops_on_color(Color::White, Color::Black), 1);
+    assert_eq!(position.bishops_on_color(Color::White, Color::White), 1);
+    assert_eq!(position.bishops_on_color(Color::Black, Color::White), 1);
+    let same_color = Fen::try_from("4k3/8/8/3b4/8/8/8/4KB2 w - - 0 1").unwrap().position().clone();
+    assert_eq!(same_color.bishops_on_color(Color::White, Color::White) + same_color.bishops_on_color(Color::Black, Color::White), 2);
+    assert!(same_color.is_insufficient_material());
+    assert!(!position.is_insufficient_material());
+}
+
+#[test]
+fn is_clearly_winning() {
+    assert!(!Board::default().is_clearly_winning(Color::White, 100));
+    assert!(!Board::default().is_clearly_winning(Color::Black, 100));
+    let board = Board::from_fen(Fen::try_from("r3k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1").unwrap());
+    assert!(board.is_clearly_winning(Color::Black, 500));
+    assert!(!board.is_clearly_winning(Color::Black, 501));
+    assert!(!board.is_clearly_winning(Color::White, 100));
+    let board = Board::from_fen(Fen::try_from("4k3/pppp4/8/8/8/8/PPPP4/R3K3 w - - 0 1").unwrap());
+    assert!(board.is_clearly_winning(Color::White, 500));
+    assert!(!board.is_clearly_winning(Color::White, 501));
+    assert!(!board.is_clearly_winning(Color::Black, 100));
+    let board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap());
+    assert!(board.is_clearly_winning(Color::White, 500));
+    assert!(!board.is_clearly_winning(Color::White, 1000));
+    assert!(!board.is_clearly_winning(Color::Black, 0));
+    let board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/1N2K1N1 w - - 0 1").unwrap());
+    assert!(!board.is_clearly_winning(Color::White, 300));
+    let mut board = Board::default();
+    board.make_moves_san("f3 e5 g4 Qh4#").unwrap();
+    assert!(board.is_clearly_winning(Color::Black, 10000));
+}
+
+#[test]
+fn king_cannot_retreat_along_check_ray() {
+    let position = Fen::try_from("4r1k1/8/8/8/4K3/8/8/8 w - - 0 1").unwrap().position().clone();
+    let king_moves = position.gen_non_illegal_moves();
+    assert_eq!(
+        king_moves,
+        [Move(28, 19, None), Move(28, 21, None), Move(28, 27, None), Move(28, 29, None), Move(28, 35, None), Move(28, 37, None)]
+    );
+    assert!(!position.is_move_legal(Move(28, 20, None)));
+    let position = Fen::try_from("6k1/b7/8/8/3K4/8/8/8 w - - 0 1").unwrap().position().clone();
+    let king_moves = position.gen_non_illegal_moves();
+    assert_eq!(king_moves.len(), 6);
+    assert!(!king_moves.contains(&Move(27, 20, None)) && !king_moves.contains(&Move(27, 34, None)));
+}
+
+#[test]
+fn san_move_map() {
+    let board = Board::default();
+    let map = board.san_move_map();
+    assert_eq!(map.len(), 20);
+    assert_eq!(map[0], ("Na3".to_owned(), Move(1, 16, None)));
+    for (san, move_) in map {
+        assert_eq!(board.san_to_move(&san).unwrap(), move_);
+    }
+}
+
+#[test]
+fn chess960_castling() {
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/RK6 w A - 0 1").unwrap());
+    assert!(board.gen_legal_moves().contains(&Move(1, 2, Some(SpecialMoveType::CastlingQueenside))));
+    board.make_move_uci("b1a1").unwrap();
+    assert_eq!(board.to_fen().to_string(), "4k3/8/8/8/8/8/8/2KR4 b - - 1 1");
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/5KR1 w K - 0 1").unwrap());
+    board.make_move_san("O-O").unwrap();
+    assert_eq!(board.to_fen().to_string(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/6KR w K - 0 1").unwrap());
+    assert!(board.gen_legal_moves().contains(&Move(6, 6, Some(SpecialMoveType::CastlingKingside))));
+    board.make_move_uci("g1h1").unwrap();
+    assert_eq!(board.to_fen().to_string(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+    let board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/RN2K3 w Q - 0 1").unwrap());
+    assert!(!board.gen_legal_moves().iter().any(|m| m.2.is_some()));
+    let fen = "rk2r3/pppppppp/8/8/8/8/PPPPPPPP/1RR1K1RR w GCkq - 0 1";
+    assert_eq!(Fen::try_from(fen).unwrap().to_string(), fen);
+    let shredder = Fen::try_from("rk2r3/pppppppp/8/8/8/8/PPPPPPPP/1RR1K1RR w GCea - 0 1").unwrap();
+    assert_eq!(Board::from_fen(shredder).to_fen().to_string(), fen);
+    assert!(Fen::try_from("4k3/8/8/8/8/8/8/R3K2R w KH - 0 1").is_err());
+}
+
+#[test]
+fn chess960_uci_castling() {
+    let board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/6KR w K - 0 1").unwrap());
+    assert_eq!(board.move_to_uci(Move(6, 6, Some(SpecialMoveType::CastlingKingside))).unwrap(), "g1h1");
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/1R2K3 w Q - 0 1").unwrap());
+    assert_eq!(board.san_to_uci("O-O-O").unwrap(), "e1b1");
+    board.make_move_uci("e1b1").unwrap();
+    assert_eq!(board.moves_uci(), ["e1b1"]);
+    let mut board = Board::default();
+    board.make_moves_uci("e2e4 e7e5 g1f3 b8c6 f1c4 g8f6 e1g1").unwrap();
+    assert_eq!(board.moves_uci().last().unwrap(), "e1g1");
+    let attacked = Board::from_fen(Fen::try_from("4r1k1/8/8/8/8/8/8/1K5R w K - 0 1").unwrap());
+    assert!(!attacked.position().is_castling(Move::from_uci("b1h1").unwrap()));
+    assert!(attacked.move_to_uci(Move(1, 6, Some(SpecialMoveType::CastlingKingside))).is_err());
+    assert!(attacked.clone().make_move_uci("b1h1").is_err());
+    let mut board = Board::from_fen(Fen::try_from("r5k1/8/8/8/8/8/8/1K5R w K - 0 1").unwrap());
+    board.make_move_uci("b1h1").unwrap();
+    assert_eq!(board.to_fen().to_string(), "r5k1/8/8/8/8/8/8/5RK1 b - - 1 1");
+    assert_eq!(board.moves_uci(), ["b1h1"]);
+}
+
+#[test]
+fn fen_errors_report_field() {
+    use super::FenParseError;
+
+    let err = |fen| Fen::try_from(fen).unwrap_err();
+    assert!(matches!(err("4k3/8/8/8/8/8/8/4K3 w - - 0"), FenParseError::SixFields));
+    assert!(matches!(err("4k3/8/8/8/8/8/4K3 w - - 0 1"), FenParseError::BoardData { field, .. } if field == "4k3/8/8/8/8/8/4K3"));
+    assert!(matches!(err("4k3/8/8/8/8/8/8/4K4 w - - 0 1"), FenParseError::BoardData { field, .. } if field == "4k3/8/8/8/8/8/8/4K4"));
+    assert!(matches!(err("4k3/8/8/8/8/8/8/4K3 white - - 0 1"), FenParseError::ActiveColor(s) if s == "white"));
+    assert!(matches!(err("4k3/8/8/8/8/8/8/4K3 w X - 0 1"), FenParseError::CastlingRights { field, .. } if field == "X"));
+    assert_eq!(
+        err("4k3/8/8/8/8/8/8/R3K3 w KQ - 0 1").to_string(),
+        "Invalid FEN castling rights 'KQ': white must have exactly one king's rook to have kingside castling rights"
+    );
+    assert!(matches!(err("4k3/8/8/8/8/8/8/4K3 w - e9 0 1"), FenParseError::EnPassantTargetSquare(s) if s == "e9"));
+    assert!(matches!(err("4k3/8/8/8/8/8/8/4K3 w - - x 1"), FenParseError::HalfmoveClock(s) if s == "x"));
+    assert!(matches!(err("4k3/8/8/8/8/8/8/4K3 w - - 0 one"), FenParseError::FullmoveNumber(s) if s == "one"));
+    assert_eq!(
+        err("4k3/8/8/8/8/8/8/4K3 w - - 0 one").to_string(),
+        "Invalid FEN fullmove number: got 'one', fullmove number must be in the range 1.."
+    );
+}
+
+#[test]
+fn fen_without_counters() {
+    use super::{Epd, InvalidEpdError};
+
+    let epd = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -";
+    let fen = Fen::from_epd(epd).unwrap();
+    assert_eq!(fen, Board::default().to_fen());
+    assert_eq!((fen.halfmove_clock(), fen.fullmove_number()), (0, 1));
+    assert_eq!(fen.to_epd(), epd);
+    let fen = Fen::from_epd("4k3/8/8/8/8/8/8/R3K2R w HA - hmvc 12; fmvn 30;").unwrap();
+    assert_eq!((fen.halfmove_clock(), fen.fullmove_number()), (12, 30));
+    assert_eq!(fen.to_epd(), "4k3/8/8/8/8/8/8/R3K2R w HA -");
+    assert_eq!(Fen::from_epd(&fen.to_epd()).unwrap().position(), fen.position());
+    assert!(Fen::from_epd("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w").is_err());
+    let epd = "4k3/8/8/8/8/8/8/4K3 w - - bm Zz9;";
+    assert!(matches!(Epd::try_from(epd), Err(InvalidEpdError::Operation(op)) if op == "bm Zz9"));
+    assert_eq!(Fen::from_epd(epd).unwrap().to_epd(), "4k3/8/8/8/8/8/8/4K3 w - -");
+    assert_eq!(Board::from_epd(epd).unwrap().to_fen().to_epd(), "4k3/8/8/8/8/8/8/4K3 w - -");
+}
+
+#[test]
+fn fen_from_components() {
+    use super::{InvalidFenError, PositionBuilder};
+    let mut builder = PositionBuilder::new();
+    builder.set_piece(4, Some(Piece(PieceType::K, Color::White))).set_piece(60, Some(Piece(PieceType::K, Color::Black)));
+    let fen = Fen::new(builder.build().unwrap(), 3, 12).unwrap();
+    assert_eq!(fen.to_string(), "4k3/8/8/8/8/8/8/4K3 w - - 3 12");
+    assert_eq!(Fen::try_from(fen.to_string().as_str()).unwrap(), fen);
+    let position = Fen::try_from("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap().position().clone();
+    assert!(matches!(Fen::new(position.clone(), 151, 1), Err(InvalidFenError::HalfmoveClock(s)) if s == "151"));
+    assert!(matches!(Fen::new(position.clone(), 0, 0), Err(InvalidFenError::FullmoveNumber(s)) if s == "0"));
+    let mut kingless = position;
+    kingless.content[4] = None;
+    kingless.castling_rights[0] = Some(7);
+    assert!(matches!(Fen::new(kingless, 0, 1), Err(InvalidFenError::BoardData { field, .. } | InvalidFenError::CastlingRights { field, .. }) if !field.is_empty()));
+    let fen = Fen::try_from("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2").unwrap();
+    assert_eq!(Fen::new(fen.position().clone(), 0, 2).unwrap(), fen);
+}
+
+#[test]
+fn validate_en_passant() {
+    use super::{InvalidFenError, PositionProblem};
+
+    let position = Fen::try_from("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2").unwrap().position().clone();
+    assert!(position.validate_en_passant());
+    assert!(Board::default().position().validate_en_passant());
+    let position = Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap().position().clone();
+    assert!(position.validate_en_passant());
+    let mut position = Board::default().position().clone();
+    position.ep_target = Some(43);
+    assert!(!position.validate_en_passant());
+    assert_eq!(position.validate(), [PositionProblem::InvalidEnPassantTarget(43)]);
+    assert!(matches!(Fen::new(position, 0, 1), Err(InvalidFenError::EnPassantTargetSquare(s)) if s == "d6"));
+    let mut position = Fen::try_from("rnbqkbnr/ppp1pppp/3p4/3P4/8/8/PPP1PPPP/RNBQKBNR w KQkq - 0 3").unwrap().position().clone();
+    position.ep_target = Some(43);
+    assert!(!position.validate_en_passant());
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e6 0 1",
+        "rnbqkbnr/ppp1pppp/3p4/3P4/8/8/PPP1PPPP/RNBQKBNR w KQkq d6 0 3",
+    ] {
+        assert!(matches!(Fen::try_from(fen), Err(InvalidFenError::EnPassantTargetSquare(s)) if s == fen.split(' ').nth(3).unwrap()));
+    }
+}
+
+#[test]
+fn shredder_fen_castling_rights() {
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1";
+    let parsed = Fen::try_from(fen).unwrap();
+    assert_eq!(parsed.castling_rook_files(), [Some('h'), Some('a'), Some('h'), Some('a')]);
+    assert_eq!(parsed.to_string(), fen);
+    assert_eq!(parsed, Board::default().to_fen());
+    assert_eq!(Board::default().to_fen().to_string(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    let parsed = Fen::try_from("1r2k1r1/8/8/8/8/8/8/1R2K1R1 w Gb - 0 1").unwrap();
+    assert_eq!(parsed.castling_rook_files(), [Some('g'), None, None, Some('b')]);
+    assert_eq!(parsed.to_string(), "1r2k1r1/8/8/8/8/8/8/1R2K1R1 w Gb - 0 1");
+}
+
+#[test]
+fn from_pgn() {
+    use super::{errors::PgnParseError, DrawType, GameResult, PgnTags, WinType};
+
+    let pgn = r#"[Event "Casual \"blitz\""]
+[Site "?"]
+[Result "0-1"]
+
+1. f3 {a weak move} e5 $2 2. g4?? (2. e4 Nc6) Qh4# 0-1"#;
+    let board = Board::from_pgn(pgn).unwrap();
+    assert_eq!(board.game_result(), Some(GameResult::Wins(Color::Black, WinType::Checkmate)));
+    assert_eq!(board.gen_movetext(), "1. f3 e5 2. g4 Qh4#");
+    let board = Board::from_pgn("[White \"A\"]\n[Black \"B\"]\n\n1. e4 e5 1/2-1/2").unwrap();
+    assert_eq!(board.game_result(), Some(GameResult::Draw(DrawType::Agreement)));
+    let board = Board::from_pgn("[Result \"1-0\"]\n\n1. d4 d5").unwrap();
+    assert_eq!(board.game_result(), Some(GameResult::Wins(Color::White, WinType::Resignation)));
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap());
+    board.make_moves_san("e4 Kd7 e5").unwrap();
+    assert_eq!(Board::from_pgn(&board.to_pgn(&PgnTags::default())).unwrap(), board);
+    assert!(matches!(Board::from_pgn("1. e4 e5 2. Ke3"), Err(PgnParseError::IllegalMove(san)) if san == "Ke3"));
+    assert!(matches!(Board::from_pgn("[Event \"x]\n1. e4"), Err(PgnParseError::TagPair(_))));
+    assert!(matches!(Board::from_pgn("[FEN \"8/8/8/8 w - - 0 1\"]\n1. e4"), Err(PgnParseError::Fen(_))));
+    assert!(matches!(Board::from_pgn("1. f3 e5 2. g4 Qh4# 1-0"), Err(PgnParseError::Result(_))));
+}
+
+#[test]
+fn material_imbalance() {
+    assert!(!Board::default().has_material_imbalance());
+    assert_eq!(Board::default().position().material_count(Color::Black).pawns, 8);
+    assert!(!Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/2B1K1N1 w - - 0 1").unwrap()).has_material_imbalance());
+    assert!(Board::from_fen(Fen::try_from("3rk3/7p/8/8/8/8/8/2B1K1N1 w - - 0 1").unwrap()).has_material_imbalance());
+    assert!(Board::from_fen(Fen::try_from("2b1k3/8/8/8/8/8/8/4K1N1 w - - 0 1").unwrap()).has_material_imbalance());
+    assert!(!Board::from_fen(Fen::try_from("4k3/7p/8/8/8/8/PP6/4K3 w - - 0 1").unwrap()).has_material_imbalance());
+    assert!(!Board::from_fen(Fen::try_from("3qk3/8/8/8/8/8/8/3RK3 w - - 0 1").unwrap()).has_material_imbalance());
+}
+
+#[test]
+fn zobrist_hash() {
+    let (mut a, mut b) = (Board::default(), Board::default());
+    a.make_moves_san("e4 e6 d4 d5 Nc3").unwrap();
+    b.make_moves_san("d4 d5 Nc3 e6 e4").unwrap();
+    assert_eq!(a.position().zobrist_hash(), b.position().zobrist_hash());
+    assert_ne!(a.position().zobrist_hash(), Board::default().position().zobrist_hash());
+    let mut c = Board::default();
+    c.make_moves_san("Nf3 Nf6 Ng1 Ng8").unwrap();
+    assert_eq!(c.position().zobrist_hash(), Board::default().position().zobrist_hash());
+    let with_ep = Fen::try_from("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    let without_ep = Fen::try_from("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_ne!(with_ep.position().zobrist_hash(), without_ep.position().zobrist_hash());
+    let uncapturable = Fen::try_from("4k3/8/8/3p4/4P3/8/8/4K3 w - d6 0 1").unwrap();
+    let uncapturable_without_ep = Fen::try_from("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(uncapturable.position().zobrist_hash(), uncapturable_without_ep.position().zobrist_hash());
+    let white_moved = Fen::try_from("4k3/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_ne!(white_moved.position().zobrist_hash(), Fen::try_from("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap().position().zobrist_hash());
+    assert_eq!(Board::default().position().zobrist_hash(), 0xbe41_9d63_be22_b1ee);
+}
+
+#[test]
+fn rook_activity() {
+    let position = Board::default().position().clone();
+    assert_eq!(position.rooks_on_seventh(Color::White), 0);
+    assert!(!position.connected_rooks(Color::White) && !position.connected_rooks(Color::Black));
+    let position = Fen::try_from("6k1/RR6/8/8/8/8/1r6/r5K1 w - - 0 1").unwrap().position().clone();
+    assert_eq!(position.rooks_on_seventh(Color::White), 2);
+    assert_eq!(position.rooks_on_seventh(Color::Black), 1);
+    assert!(position.connected_rooks(Color::White));
+    assert!(!position.connected_rooks(Color::Black));
+    let position = Fen::try_from("r3r1k1/8/8/8/8/8/8/R2QR1K1 w - - 0 1").unwrap().position().clone();
+    assert!(!position.connected_rooks(Color::White));
+    assert!(position.connected_rooks(Color::Black));
+    assert!(Fen::try_from("6k1/8/8/8/R7/8/8/R5K1 w - - 0 1").unwrap().position().connected_rooks(Color::White));
+}
+
+#[test]
+fn fen_history() {
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5 Nf3").unwrap();
+    let fens: Vec<_> = board.fen_history().iter().map(|f| f.to_string()).collect();
+    assert_eq!(
+        fens,
+        [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2",
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2",
+        ]
+    );
+    assert_eq!(
+        board.movetext_with_fen_comments(),
+        "1. e4 {rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1} e5 {rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2} 2. Nf3 {rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2}"
+    );
+    let mut board = Board::from_fen(Fen::try_from("4k3/p7/8/8/8/8/P7/4K3 b - - 10 40").unwrap());
+    board.make_moves_san("Kd7 Kd2").unwrap();
+    assert_eq!(
+        board.movetext_with_fen_comments(),
+        "40... Kd7 {8/p2k4/8/8/8/8/P7/4K3 w - - 11 41} 41. Kd2 {8/p2k4/8/8/8/8/P2K4/8 b - - 12 41}"
+    );
+    assert_eq!(Board::default().movetext_with_fen_comments(), "");
+}
+
+#[test]
+fn perft() {
+    let board = Board::default();
+    assert_eq!((0..=3).map(|depth| board.perft(depth)).collect::<Vec<_>>(), [1, 20, 400, 8902]);
+    let divide = board.perft_divide(2);
+    assert_eq!(divide.len(), 20);
+    assert!(divide.iter().all(|&(_, count)| count == 20));
+    let kiwipete = Board::from_fen(Fen::try_from("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap());
+    assert_eq!((1..=3).map(|depth| kiwipete.perft(depth)).collect::<Vec<_>>(), [48, 2039, 97862]);
+    let position3 = Board::from_fen(Fen::try_from("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap());
+    assert_eq!((1..=4).map(|depth| position3.perft(depth)).collect::<Vec<_>>(), [14, 191, 2812, 43238]);
+    let position4 = Board::from_fen(Fen::try_from("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap());
+    assert_eq!((1..=3).map(|depth| position4.perft(depth)).collect::<Vec<_>>(), [6, 264, 9467]);
+}
+
+#[test]
+#[ignore]
+fn perft_deep() {
+    assert_eq!(Board::default().perft(4), 197281);
+    assert_eq!(Board::default().perft(5), 4865609);
+    let kiwipete = Board::from_fen(Fen::try_from("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap());
+    assert_eq!(kiwipete.perft(4), 4085603);
+}
+
+#[test]
+fn make_unmake_in_place() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1",
+        "4k3/8/8/8/8/8/8/5KR1 w K - 0 1",
+    ];
+    for fen in fens {
+        let original = Fen::try_from(fen).unwrap().position().clone();
+        let mut position = original.clone();
+        for move_ in original.gen_non_illegal_moves() {
+            let undo = position.make_move_in_place(move_).unwrap();
+            assert_eq!(position, original.with_move_made(move_).unwrap());
+            position.unmake_move(undo);
+            assert_eq!(position, original);
+        }
+    }
+    let mut position = Board::default().position().clone();
+    assert!(position.make_move_in_place(Move(12, 36, None)).is_err());
+    assert_eq!(&position, Board::default().position());
+}
+
+#[test]
+fn checking_moves() {
+    assert!(Board::default().position().checking_moves().is_empty());
+    let position = Fen::try_from("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap().position().clone();
+    let castling = Move(4, 2, Some(SpecialMoveType::CastlingQueenside));
+    assert_eq!(position.checking_moves(), [Move(0, 3, None), Move(0, 56, None), castling]);
+    assert!(position.gives_check(castling).unwrap());
+    assert!(!position.gives_check(Move(4, 5, None)).unwrap());
+    assert!(position.gives_check(Move(4, 20, None)).is_err());
+    let position = Fen::try_from("4k3/8/8/8/8/8/8/5KR1 w K - 0 1").unwrap().position().clone();
+    assert!(!position.is_capture(Move(5, 6, Some(SpecialMoveType::CastlingKingside))).unwrap());
+}
+
+#[test]
+fn move_gives_check() {
+    let position = |fen| Fen::try_from(fen).unwrap().position().clone();
+    let direct = position("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+    assert!(direct.move_gives_check(Move(0, 56, None)));
+    assert!(!direct.move_gives_check(Move(0, 48, None)));
+    let discovered = position("6k1/8/8/3N4/8/8/B7/4K3 w - - 0 1");
+    assert!(discovered.move_gives_check(Move(35, 25, None)));
+    assert!(discovered.move_gives_check(Move(35, 52, None)));
+    assert!(!discovered.move_gives_check(Move(4, 3, None)));
+    let promotion = position("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1");
+    assert!(promotion.move_gives_check(Move(49, 57, Some(SpecialMoveType::Promotion(PieceType::Q)))));
+    assert!(!promotion.move_gives_check(Move(49, 57, Some(SpecialMoveType::Promotion(PieceType::N)))));
+    let en_passant = position("8/8/8/R2Pp2k/8/8/8/4K3 w - e6 0 1");
+    assert!(en_passant.move_gives_check(Move(35, 44, Some(SpecialMoveType::EnPassant))));
+    assert!(!en_passant.move_gives_check(Move(35, 43, None)));
+    assert!(!direct.move_gives_check(Move(0, 57, None)));
+    for position in [direct, discovered, promotion, en_passant, Board::default().position().clone()] {
+        for m in position.gen_non_illegal_moves() {
+            assert_eq!(position.move_gives_check(m), position.with_move_made(m).unwrap().is_check(), "{m}");
+        }
+    }
+}
+
+#[test]
+fn last_irreversible_ply() {
+    let mut board = Board::default();
+    assert_eq!(board.last_irreversible_ply(), None);
+    board.make_moves_san("Nf3 Nf6 e4").unwrap();
+    assert_eq!(board.last_irreversible_ply(), Some(2));
+    board.make_moves_san("Nxe4 Be2 Nf6 O-O Nc6 Nc3").unwrap();
+    assert_eq!(board.last_irreversible_ply(), Some(6));
+    assert_eq!(board.plies_since_last_capture_or_pawn_move(), 5);
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 7 30").unwrap());
+    board.make_moves_san("Ra2 Kd7").unwrap();
+    assert_eq!(board.last_irreversible_ply(), None);
+    assert_eq!(board.plies_since_last_capture_or_pawn_move(), 9);
+}
+
+#[test]
+fn board_legal_moves_from() {
+    let board = Board::default();
+    assert_eq!(board.legal_moves_from('g', '1').unwrap(), [Move(6, 21, None), Move(6, 23, None)]);
+    assert!(board.legal_moves_from('e', '4').unwrap().is_empty());
+    assert!(board.legal_moves_from('i', '1').is_err());
+    let board = Board::from_fen(Fen::try_from("4k3/1P6/8/8/8/8/8/4K2R w K - 0 1").unwrap());
+    let promotions = board.legal_moves_from('b', '7').unwrap();
+    assert_eq!(promotions.len(), 4);
+    assert!(promotions.iter().all(|m| m.0 == 49 && m.1 == 57 && matches!(m.2, Some(SpecialMoveType::Promotion(_)))));
+    assert!(board.legal_moves_from('e', '1').unwrap().contains(&Move(4, 6, Some(SpecialMoveType::CastlingKingside))));
+    let mut board = Board::default();
+    board.make_moves_san("f3 e5 g4 Qh4#").unwrap();
+    assert!(board.legal_moves_from('e', '8').unwrap().is_empty());
+}
+
+#[test]
+fn make_move_default_promotion() {
+    let mut board = Board::from_fen(Fen::try_from("4k3/1P6/8/8/8/8/8/4K2R w K - 0 1").unwrap());
+    let mut queened = board.clone();
+    queened.make_move_default_promotion(49, 57).unwrap();
+    assert_eq!(queened.move_history(), [Move(49, 57, Some(SpecialMoveType::Promotion(PieceType::Q)))]);
+    assert_eq!(queened.position().content[57], Some(Piece(PieceType::Q, Color::White)));
+    let mut castled = board.clone();
+    castled.make_move_default_promotion(4, 6).unwrap();
+    assert_eq!(castled.move_history(), [Move(4, 6, Some(SpecialMoveType::CastlingKingside))]);
+    board.make_move_default_promotion(7, 15).unwrap();
+    assert_eq!(board.move_history(), [Move(7, 15, None)]);
+    assert!(board.make_move_default_promotion(60, 44).is_err());
+    assert_eq!(board.ply_count(), 1);
+}
+
+#[test]
+fn move_classification() {
+    let queening = Move(49, 57, Some(SpecialMoveType::Promotion(PieceType::Q)));
+    assert_eq!(queening.promotion(), Some(PieceType::Q));
+    assert_eq!(Move(12, 28, None).promotion(), None);
+    assert_eq!(Move::from_uci("b7b8n").unwrap().promotion(), Some(PieceType::N));
+    let position = Fen::try_from("4k3/8/8/3pP3/8/8/8/R3K2R w KQ d6 0 1").unwrap().position().clone();
+    let unclear = |uci| Move::from_uci(uci).unwrap();
+    assert!(position.is_castling(Move(4, 6, Some(SpecialMoveType::CastlingKingside))));
+    assert!(position.is_castling(unclear("e1c1")));
+    assert!(position.is_castling(unclear("e1h1")));
+    assert!(!position.is_castling(unclear("e1f1")));
+    assert!(!position.is_castling(unclear("e5d6")));
+    assert!(position.is_en_passant(unclear("e5d6")));
+    assert!(!position.is_en_passant(unclear("e5e6")));
+    assert!(!position.is_en_passant(unclear("e1c1")));
+    assert!(!position.is_en_passant(Move(36, 44, Some(SpecialMoveType::EnPassant))));
+}
+
+#[test]
+fn legal_moves_iter() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "4k3/8/8/8/8/5b2/8/4K2R w K - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ];
+    for fen in fens {
+        let board = Board::from_fen(Fen::try_from(fen).unwrap());
+        assert_eq!(board.legal_moves_iter().collect::<Vec<_>>(), board.gen_legal_moves());
+        assert_eq!(board.position().legal_moves_iter().collect::<Vec<_>>(), board.gen_legal_moves());
+    }
+    let mut board = Board::default();
+    assert_eq!(board.legal_moves_iter().next(), Some(Move(1, 16, None)));
+    board.make_moves_san("f3 e5 g4 Qh4#").unwrap();
+    assert_eq!(board.legal_moves_iter().count(), 0);
+}
+
+#[test]
+fn san_variants() {
+    let position = Fen::try_from("4k3/8/8/3pP3/8/8/8/R3K2R w KQ d6 0 1").unwrap().position().clone();
+    let ep = Move(36, 43, Some(SpecialMoveType::EnPassant));
+    for san in ["exd6", "exd6e.p.", "exd6 e.p.", "exd6+", "exd6e.p.!?"] {
+        assert_eq!(position.san_to_move(san).unwrap(), ep);
+    }
+    let oo = Move(4, 6, Some(SpecialMoveType::CastlingKingside));
+    let ooo = Move(4, 2, Some(SpecialMoveType::CastlingQueenside));
+    assert_eq!(position.san_to_move("O-O+").unwrap(), oo);
+    assert_eq!(position.san_to_move("0-0").unwrap(), oo);
+    assert_eq!(position.san_to_move("0-0-0").unwrap(), ooo);
+    assert_eq!(position.san_to_move("O-O-O!").unwrap(), ooo);
+    assert_eq!(position.san_to_move("Ra8#").unwrap(), position.san_to_move("Ra8+").unwrap());
+    for san in ["", "e.p.", "Ra+8", "exd6e.p.e.p.", "0-O-0-", "Rb1x", "O-O-O-O"] {
+        assert_eq!(position.san_to_move(san).unwrap_err().0, san);
+    }
+}
+
+#[test]
+fn rotate_180() {
+    let position = Fen::try_from("r3k2r/ppp1qppp/2n5/3pP3/8/8/PPP2PPP/R3K2R w KQkq d6 0 1").unwrap().position().clone();
+    let rotated = position.rotate_180();
+    assert_eq!(rotated.to_fen(), "r2k3r/ppp2ppp/8/8/3pP3/5N2/PPPQ1PPP/R2K3R b KQkq e3");
+    assert!(rotated.validate().is_empty());
+    assert_eq!(rotated.rotate_180(), position);
+    assert_eq!(rotated.gen_non_illegal_moves().len(), position.gen_non_illegal_moves().len());
+    assert_eq!(Board::default().position().rotate_180().to_fen(), "rnbkqbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBKQBNR b KQkq -");
+}
+
+#[cfg(feature = "img")]
+#[test]
+fn render_frames() {
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5").unwrap();
+    let frames = board.render_frames(Color::White, 16).unwrap();
+    assert_eq!(frames.len(), 3);
+    for frame in &frames {
+        let image = image::load_from_memory_with_format(frame, image::ImageFormat::Png).unwrap();
+        assert_eq!((image.width(), image.height()), (128, 128));
+    }
+    assert_ne!(frames[0], frames[1]);
+    assert!(board.render_frames(Color::White, 0).is_err());
+}
+
+#[test]
+fn attackers_of() {
+    let position = Fen::try_from("4k3/8/3p4/4P3/8/8/8/4K3 w - - 0 1").unwrap().position().clone();
+    assert_eq!(position.attackers_of(helpers::sq_to_idx('d', '6'), Color::White), [36]);
+    assert_eq!(position.attackers_of(helpers::sq_to_idx('e', '5'), Color::Black), [43]);
+    assert!(position.attackers_of(helpers::sq_to_idx('e', '6'), Color::White).is_empty());
+    assert!(!position.is_attacked_by(helpers::sq_to_idx('e', '4'), Color::Black));
+    let fork = Fen::try_from("r3k3/8/8/1N6/8/8/8/4K3 w - - 0 1").unwrap().position().clone();
+    let knight = helpers::sq_to_idx('c', '7');
+    let fork = fork.with_move_made(Move(33, knight, None)).unwrap();
+    assert_eq!(fork.attackers_of(60, Color::White), [knight]);
+    assert_eq!(fork.attackers_of(56, Color::White), [knight]);
+    let blocked = Fen::try_from("4k3/8/8/8/8/8/4N3/4RK2 w - - 0 1").unwrap().position().clone();
+    assert!(!blocked.is_attacked_by(60, Color::White));
+    assert_eq!(blocked.attackers_of(helpers::sq_to_idx('e', '2'), Color::White), [4, 5]);
+    let discovered = blocked.with_move_made(Move(12, 29, None)).unwrap();
+    assert_eq!(discovered.attackers_of(60, Color::White), [4]);
+    assert!(discovered.is_attacked_by(60, Color::White));
+}
+
+#[test]
+fn pinned_pieces() {
+    let position = Fen::try_from("4r1k1/8/8/b7/8/8/3N4/4RK2 w - - 0 1").unwrap().position().clone();
+    assert!(position.pinned_pieces(Color::White).is_empty());
+    let position = Fen::try_from("4r1k1/8/8/8/1b6/8/3N4/4K3 w - - 0 1").unwrap().position().clone();
+    assert_eq!(position.pinned_pieces(Color::White), [(11, 25)]);
+    assert!(position.is_pinned(11) && !position.is_pinned(25) && !position.is_pinned(30));
+    let position = Fen::try_from("4r1k1/8/8/8/8/4B3/4N3/4K3 w - - 0 1").unwrap().position().clone();
+    assert!(position.pinned_pieces(Color::White).is_empty());
+    let position = Fen::try_from("6k1/8/8/b7/8/8/3N4/r2RK3 w - - 0 1").unwrap().position().clone();
+    assert_eq!(position.pinned_pieces(Color::White), [(3, 0), (11, 32)]);
+    let position = Fen::try_from("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap().position().clone();
+    assert_eq!(position.pinned_pieces(Color::White), [(12, 60)]);
+    assert!(position.pinned_pieces(Color::Black).is_empty());
+    assert!(Fen::try_from("3r2k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap().position().pinned_pieces(Color::White).is_empty());
+}
+
+#[test]
+fn can_checkmate_with_current_material() {
+    let can_mate = |fen: &str| Fen::try_from(fen).unwrap().position().can_checkmate_with_current_material(Color::White);
+    assert!(Board::default().position().can_checkmate_with_current_material(Color::Black));
+    for fen in ["4k3/8/8/8/8/8/8/3QK3 w - - 0 1", "4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "4k3/8/8/8/8/8/P7/4K3 w - - 0 1"] {
+        assert!(can_mate(fen));
+    }
+    for fen in ["4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1", "4k3/8/8/8/8/8/8/2B1K1N1 w - - 0 1", "4k3/8/8/8/8/8/8/1N2KNN1 w - - 0 1"] {
+        assert!(can_mate(fen));
+    }
+    for fen in [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KN2 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KB2 w - - 0 1",
+        "q3k3/8/8/8/8/8/8/4K1NN w - - 0 1",
+    ] {
+        assert!(!can_mate(fen));
+    }
+    assert!(!can_mate("4k3/8/8/8/8/8/1B6/2B1K3 w - - 0 1"));
+}
+
+#[test]
+fn make_moves_with() {
+    let mut board = Board::default();
+    let moves = [Move(12, 28, None), Move(52, 36, None), Move(6, 21, None)];
+    let mut seen = Vec::new();
+    board.make_moves_with(&moves, |b, m| seen.push((b.fullmove_number(), m))).unwrap();
+    assert_eq!(seen, [(1, moves[0]), (2, moves[1]), (2, moves[2])]);
+    assert_eq!(board.gen_movetext(), "1. e4 e5 2. Nf3");
+    let mut count = 0;
+    let before = board.clone();
+    assert!(board.make_moves_with(&[Move(57, 42, None), Move(21, 21, None)], |_, _| count += 1).is_err());
+    assert_eq!(count, 1);
+    assert_eq!(board, before);
+}
+
+#[test]
+fn en_passant_square() {
+    use super::InvalidFenError;
+
+    let mut board = Board::default();
+    board.make_move_san("e4").unwrap();
+    assert_eq!(board.position().en_passant_square(), Some(20));
+    let fen = Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+    assert_eq!(fen.en_passant_square(), Some(('e', '3')));
+    assert_eq!(fen, board.to_fen());
+    assert_eq!(Board::default().to_fen().en_passant_square(), None);
+    assert!(matches!(
+        Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e3 0 1"),
+        Err(InvalidFenError::EnPassantTargetSquare(_))
+    ));
+    assert!(matches!(
+        Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e6 0 1"),
+        Err(InvalidFenError::EnPassantTargetSquare(_))
+    ));
+    assert!(matches!(
+        Fen::try_from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e4 0 1"),
+        Err(InvalidFenError::EnPassantTargetSquare(_))
+    ));
+}
+
+#[test]
+fn moves_until_move_rules() {
+    let mut board = Board::default();
+    assert_eq!((board.moves_until_fifty_move_rule(), board.moves_until_seventy_five_move_rule()), (100, 150));
+    board.make_moves_san("Nf3 Nf6").unwrap();
+    assert_eq!((board.moves_until_fifty_move_rule(), board.moves_until_seventy_five_move_rule()), (98, 148));
+    let board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 120 90").unwrap());
+    assert_eq!((board.moves_until_fifty_move_rule(), board.moves_until_seventy_five_move_rule()), (0, 30));
+    let board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/R3K3 w - - 150 90").unwrap());
+    assert_eq!(board.moves_until_seventy_five_move_rule(), 0);
+}
+
+#[test]
+fn to_pgn_with_evals() {
+    use super::PgnTags;
+
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5 Qh5 Ke7 Qxe5#").unwrap();
+    let pgn = board.to_pgn_with_evals(&PgnTags::default(), |position| position.material_balance() * 100 - 5);
+    assert!(pgn.ends_with("1. e4 {[%eval -0.05]} e5 {[%eval -0.05]} 2. Qh5 {[%eval -0.05]} Ke7\n{[%eval -0.05]} 3. Qxe5# {[%eval 0.95]} 1-0"));
+    assert_eq!(Board::from_pgn(&pgn).unwrap(), board);
+    let mut board = Board::default();
+    board.make_moves_san("e4 e5").unwrap();
+    let pgn = board.to_pgn_with_evals(&PgnTags::default(), |position| if position.side_to_move().is_black() { i32::MIN } else { i32::MAX });
+    assert!(pgn.ends_with("1. e4 {[%eval -21474836.48]} e5 {[%eval 21474836.47]} *"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    use super::{Position, TimeControl};
+    use std::time::Duration;
+
+    let mut board = Board::default();
+    board.set_time_control(TimeControl {
+        initial: Duration::from_secs(180),
+        increment: Duration::from_secs(2),
+        delay: Duration::ZERO,
+    });
+    for (i, san) in ["e4", "e5", "Nf3", "Nc6", "Bc4", "Nf6", "O-O"].into_iter().enumerate() {
+        board.make_move_san(san).unwrap();
+        board.on_move_played(Duration::from_millis(1500 * i as u64));
+    }
+    board.offer_draw(Color::White).unwrap();
+    let json = serde_json::to_string(&board).unwrap();
+    assert!(json.contains(r#""moves":["e2e4","e7e5","g1f3","b8c6","f1c4","g8f6","e1g1"]"#));
+    assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    assert!(serde_json::from_str::<Board>(&json.replace("g1f3", "g1g3")).is_err());
+    assert!(serde_json::from_str::<Board>(&json.replace(r#""draw_reason":null"#, r#""draw_reason":"Agreement""#)).is_ok());
+    assert!(serde_json::from_str::<Board>(&json.replace(r#""draw_reason":null"#, r#""draw_reason":"FiftyMoveRule""#)).is_err());
+    let mut board = Board::from_fen(Fen::try_from("4k3/8/8/8/8/8/8/6KR w K - 0 1").unwrap());
+    board.make_move_uci("g1h1").unwrap();
+    board.resign(Color::Black).unwrap();
+    let json = serde_json::to_string(&board).unwrap();
+    assert!(json.contains(r#""moves":["g1h1"]"#) && !json.contains("clock_history"));
+    assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    let fen = Fen::try_from("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2").unwrap();
+    let json = serde_json::to_string(&fen).unwrap();
+    assert_eq!(json, r#""rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2""#);
+    assert_eq!(serde_json::from_str::<Fen>(&json).unwrap(), fen);
+    assert!(serde_json::from_str::<Fen>(r#""8/8/8/8/8/8/8/8 w - - 0 1""#).is_err());
+    let position = fen.position().clone();
+    assert_eq!(serde_json::to_string(&position).unwrap(), r#""rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6""#);
+    assert_eq!(serde_json::from_str::<Position>(&serde_json::to_string(&position).unwrap()).unwrap(), position);
+    let promotion = Move(52, 60, Some(SpecialMoveType::Promotion(PieceType::Q)));
+    assert_eq!(serde_json::to_string(&promotion).unwrap(), r#""e7e8q""#);
+    assert_eq!(serde_json::from_str::<Move>(r#""e7e8q""#).unwrap(), promotion);
+    assert_eq!(serde_json::from_str::<Move>(r#""e2e4""#).unwrap(), Move(12, 28, Some(SpecialMoveType::Unclear)));
+    assert!(serde_json::from_str::<Move>(r#""e2""#).is_err());
+    let piece = Piece(PieceType::N, Color::Black);
+    assert_eq!(serde_json::from_str::<Piece>(&serde_json::to_string(&piece).unwrap()).unwrap(), piece);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn ron_and_toml_round_trip() {
+    use super::TimeControl;
+    use std::time::Duration;
+
+    #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+    struct Saved {
+        fen: Fen,
+    }
+
+    let mut board = Board::default();
+    board.set_time_control(TimeControl {
+        initial: Duration::from_secs(300),
+        increment: Duration::ZERO,
+        delay: Duration::ZERO,
+    });
+    board.make_moves_san("d4 d5 c4 dxc4 e4").unwrap();
+    board.on_move_played(Duration::from_secs(4));
+    let ron = ron::to_string(&board).unwrap();
+    assert!(ron.starts_with(r#"(initial_fen:"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",moves:["d2d4","d7d5","c2c4","d5c4","e2e4"],"#));
+    assert_eq!(ron::from_str::<Board>(&ron).unwrap(), board);
+    let toml = toml::to_string(&board).unwrap();
+    assert!(toml.starts_with("initial_fen = \"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\"\nmoves = [\"d2d4\", \"d7d5\", \"c2c4\", \"d5c4\", \"e2e4\"]\n"));
+    assert_eq!(toml::from_str::<Board>(&toml).unwrap(), board);
+    board.resign(Color::White).unwrap();
+    let mut untimed = Board::default();
+    untimed.make_moves_san("e4 c5 Nf3").unwrap();
+    untimed.resign(Color::Black).unwrap();
+    assert_eq!(toml::from_str::<Board>(&toml::to_string(&untimed).unwrap()).unwrap(), untimed);
+    assert_eq!(ron::from_str::<Board>(&ron::to_string(&board).unwrap()).unwrap(), board);
+    let saved = Saved { fen: board.to_fen() };
+    let ron = ron::to_string(&saved).unwrap();
+    assert_eq!(ron, r#"(fen:"rnbqkbnr/ppp1pppp/8/8/2pPP3/8/PP3PPP/RNBQKBNR b KQkq e3 0 3")"#);
+    assert_eq!(ron::from_str::<Saved>(&ron).unwrap(), saved);
+    let toml = toml::to_string(&saved).unwrap();
+    assert_eq!(toml, "fen = \"rnbqkbnr/ppp1pppp/8/8/2pPP3/8/PP3PPP/RNBQKBNR b KQkq e3 0 3\"\n");
+    assert_eq!(toml::from_str::<Saved>(&toml).unwrap(), saved);
+}
@@ -0,0 +1,56 @@
+use super::Color;
+use std::time::Duration;
+
+/// Represents the time control of a game: the time each side starts with, the increment added after every move,
+/// and the delay that elapses before a side's clock starts running down on each move.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeControl {
+    /// The time each side has at the start of the game
+    pub initial: Duration,
+    /// The time added to a side's clock after each of its moves
+    pub increment: Duration,
+    /// The amount of time at the start of each move that is not deducted from a side's clock
+    pub delay: Duration,
+}
+
+impl TimeControl {
+    /// Creates a time control with the given initial time and increment, and no delay.
+    pub fn new(initial: Duration, increment: Duration) -> Self {
+        Self {
+            initial,
+            increment,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+/// Represents the state of a game's clock: the time remaining on each side's clock and the increment added after every move.
+/// A clock is attached to a board with [`Board::set_clock`](super::Board::set_clock), after which the time taken for each move
+/// is recorded with [`Board::on_move_played`](super::Board::on_move_played).
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Clock {
+    /// The time remaining on white's clock
+    pub white: Duration,
+    /// The time remaining on black's clock
+    pub black: Duration,
+    /// The time added to a side's clock after each of its moves
+    pub increment: Duration,
+}
+
+impl Clock {
+    /// Creates a clock with the same time remaining for both sides and the given increment.
+    pub fn new(time: Duration, increment: Duration) -> Self {
+        Self { white: time, black: time, increment }
+    }
+
+    /// Returns the time remaining on the clock of the side `color`.
+    pub fn remaining(&self, color: Color) -> Duration {
+        if color.is_white() {
+            self.white
+        } else {
+            self.black
+        }
+    }
+}
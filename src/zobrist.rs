@@ -0,0 +1,70 @@
+//! Zobrist hashing of positions; the key layout is documented on [`Position::zobrist_hash`].
+
+use super::{Piece, PieceType, Position};
+
+const SEED: u64 = 0x7273_6368_6573_7321;
+const SIDE: usize = 768;
+const CASTLING: usize = 769;
+const EN_PASSANT: usize = 773;
+
+/// The Zobrist keys, generated at compile time.
+const KEYS: [u64; 781] = {
+    let mut keys = [0; 781];
+    let mut state = SEED;
+    let mut i = 0;
+    while i < keys.len() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        keys[i] = mix(state);
+        i += 1;
+    }
+    keys
+};
+
+/// Scrambles the bits of a value with the SplitMix64 output function.
+pub const fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Returns the key for the given piece on the given square.
+fn piece_key(Piece(piece_type, color): Piece, sq: usize) -> u64 {
+    let piece = match piece_type {
+        PieceType::P => 0,
+        PieceType::N => 1,
+        PieceType::B => 2,
+        PieceType::R => 3,
+        PieceType::Q => 4,
+        PieceType::K => 5,
+    } + 6 * color.index();
+    KEYS[64 * piece + sq]
+}
+
+/// Computes the Zobrist hash of the position.
+pub fn hash(position: &Position) -> u64 {
+    let mut hash = 0;
+    for (sq, piece) in position.content.iter().enumerate() {
+        if let Some(piece) = piece {
+            hash ^= piece_key(*piece, sq);
+        }
+    }
+    if position.side.is_black() {
+        hash ^= KEYS[SIDE];
+    }
+    for (i, right) in position.castling_rights.iter().enumerate() {
+        if right.is_some() {
+            hash ^= KEYS[CASTLING + i];
+        }
+    }
+    if let Some(ep_target) = position.ep_target {
+        let capturer_rank = if position.side.is_white() { 32 } else { 24 };
+        let file = ep_target % 8;
+        let capturable = [file.wrapping_sub(1), file + 1]
+            .into_iter()
+            .any(|f| f < 8 && position.content[capturer_rank + f] == Some(Piece(PieceType::P, position.side)));
+        if capturable {
+            hash ^= KEYS[EN_PASSANT + file];
+        }
+    }
+    hash
+}